@@ -0,0 +1,23 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// How [`crate::FluentCodes::any_word`] draws the word type of a randomly chosen word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnyWordMode {
+    /// Every word type with words in range is equally likely, so words of small tables are
+    /// drawn more often than words of large ones
+    #[default]
+    UniformTable,
+    /// Word types are weighted by their number of words in range, so every word is equally
+    /// likely
+    ProportionalToSize,
+}
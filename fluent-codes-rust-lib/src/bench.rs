@@ -0,0 +1,49 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+/// Timings of [`crate::FluentCodes::benchmark`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchStats {
+    /// Number of codes generated
+    pub codes: usize,
+    /// Number of those codes that failed to generate
+    pub failed: usize,
+    /// Time taken by all codes
+    pub total: Duration,
+    /// Average time taken by one code
+    pub per_code: Duration,
+    /// Codes generated per second
+    pub codes_per_sec: f64,
+}
+
+impl BenchStats {
+    pub(crate) fn new(codes: usize, failed: usize, total: Duration) -> Self {
+        let seconds = total.as_secs_f64();
+        BenchStats {
+            codes,
+            failed,
+            total,
+            per_code: if codes == 0 {
+                Duration::ZERO
+            } else {
+                total.div_f64(codes as f64)
+            },
+            codes_per_sec: if seconds > 0.0 {
+                codes as f64 / seconds
+            } else {
+                0.0
+            },
+        }
+    }
+}
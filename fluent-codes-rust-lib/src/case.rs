@@ -0,0 +1,48 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Letter case applied to the words of a code when it is displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// `fluffy-vacuum`
+    Lower,
+    /// `FLUFFY-VACUUM`
+    Upper,
+    /// `Fluffy-Vacuum`
+    Title,
+    /// `Fluffy-vacuum`
+    Sentence,
+}
+
+impl Case {
+    /// Applies the case to the word at `index` of a code
+    pub(crate) fn apply(&self, index: usize, word: &str) -> String {
+        match self {
+            Case::Lower => word.to_lowercase(),
+            Case::Upper => word.to_uppercase(),
+            Case::Title => capitalize(word),
+            Case::Sentence if index == 0 => capitalize(word),
+            Case::Sentence => word.to_lowercase(),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,59 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::RangeInclusive;
+
+use crate::WordType;
+
+/// A planned component of a code
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Component {
+    /// A word of the given type
+    Word(WordType),
+    /// A word of a type drawn for each code, see [`crate::FluentCodes::any_word`]
+    AnyWord,
+    /// The given number of words of types drawn for each code, see
+    /// [`crate::FluentCodes::random_structure`]
+    RandomStructure(usize),
+    /// Nouns and a digit block filling the given number of characters, give or take the
+    /// given tolerance, see [`crate::FluentCodes::for_length`]
+    FitLength(usize, usize),
+    /// A digit block of the given width
+    Digits(usize),
+    /// A digit block of the given width with its value, drawn when chained by
+    /// [`crate::FluentCodes::six_digits_with`]
+    DrawnDigits(usize, u64),
+    /// A block of the given number of hexadecimal digits
+    Hex(usize),
+    /// A number of the given range, zero-padded to the width of its upper bound
+    Number(RangeInclusive<u64>),
+    /// The current time as base36 seconds since the Unix epoch, chained by
+    /// `FluentCodes::with_timestamp_base36` with the `time` feature
+    Timestamp,
+    /// Fixed text
+    Literal(String),
+}
+
+impl Component {
+    /// Whether the component is displayed as a digit block, joined by
+    /// [`crate::FluentCodes::with_number_separator`]
+    pub(crate) fn is_digits(&self) -> bool {
+        matches!(
+            self,
+            Component::Digits(_) | Component::DrawnDigits(..) | Component::Number(_)
+        )
+    }
+    /// Whether the component is moved by [`crate::FluentCodes::with_digits_last`]
+    pub(crate) fn is_numeric(&self) -> bool {
+        self.is_digits() || matches!(self, Component::Hex(_) | Component::Timestamp)
+    }
+}
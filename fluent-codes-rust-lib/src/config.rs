@@ -0,0 +1,150 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::{
+    AnyWordMode, Case, Component, OutputCase, SelectionMode, WordType, DEFAULT_JOINER,
+    DEFAULT_LEET_MAP, DEFAULT_MAX_LENGTH, DEFAULT_MAX_RETRIES, DEFAULT_MIN_LENGTH,
+    DEFAULT_MIN_POOL_SIZE,
+};
+
+/// Configuration of a generator, without its word source connection or generated words.
+///
+/// Every field mirrors the [`crate::FluentCodes`] builder method of the same name, and
+/// [`crate::FluentCodes::from_config`] creates a generator from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FluentCodesConfig {
+    /// Components of the code, generated in order
+    pub structure: Vec<Component>,
+    pub joiner: String,
+    /// Joiner of the last gap, `joiner` when `None`
+    pub final_joiner: Option<String>,
+    /// Joiner of the gaps before digit blocks and numbers
+    pub number_separator: Option<String>,
+    /// Escape character of joiners inside components
+    pub joiner_escape: Option<char>,
+    pub digits_last: bool,
+    pub random_layout: bool,
+    pub min_length: usize,
+    pub max_length: usize,
+    /// Shortest and longest word length of word types, `min_length` and `max_length` when
+    /// unlisted
+    pub default_length_map: HashMap<WordType, (usize, usize)>,
+    pub auto_clamp: bool,
+    pub joiner_safe: bool,
+    pub symbol_joiners: bool,
+    /// Joiners drawn per gap with their relative weights
+    pub weighted_joiners: Vec<(String, u32)>,
+    pub shuffled_digits: bool,
+    pub no_leading_zero: bool,
+    pub min_vowel_ratio: Option<f64>,
+    pub min_distinct_initials: usize,
+    pub min_pool_size: usize,
+    pub homophones: Vec<Vec<String>>,
+    pub max_retries: usize,
+    pub require_noun: bool,
+    pub min_words: usize,
+    pub phrase_determiner: bool,
+    /// Relative weights of randomly drawn word types, 1 when unlisted
+    pub pos_weights: HashMap<WordType, u32>,
+    pub any_word_mode: AnyWordMode,
+    pub selection_mode: SelectionMode,
+    pub case: Option<Case>,
+    pub boundary_case: Option<Case>,
+    pub output_case: OutputCase,
+    pub ascii_fold: bool,
+    /// Normalization form of the components, ignored without the `unicode-normalization`
+    /// feature
+    pub unicode_normalization: Option<crate::Form>,
+    /// Group size and separator of digit blocks
+    pub digit_grouping: Option<(usize, char)>,
+    pub hex_uppercase: bool,
+    pub terminal_punctuation: bool,
+    pub require_nonempty: bool,
+    pub random_caps: bool,
+    pub pos_labels: bool,
+    pub leet: bool,
+    /// Letter substitutions of `leet`
+    pub leet_map: HashMap<char, char>,
+    /// Seed of the random number generator, taken from the OS when `None`
+    pub seed: Option<u64>,
+    /// Database file words are selected from, the bundled database when `None`
+    pub db_path: Option<PathBuf>,
+}
+
+impl Default for FluentCodesConfig {
+    fn default() -> Self {
+        FluentCodesConfig {
+            structure: vec![],
+            joiner: DEFAULT_JOINER.to_string(),
+            final_joiner: None,
+            number_separator: None,
+            joiner_escape: None,
+            digits_last: false,
+            random_layout: false,
+            min_length: DEFAULT_MIN_LENGTH,
+            max_length: DEFAULT_MAX_LENGTH,
+            default_length_map: HashMap::new(),
+            auto_clamp: false,
+            joiner_safe: false,
+            symbol_joiners: false,
+            weighted_joiners: vec![],
+            shuffled_digits: false,
+            no_leading_zero: false,
+            min_vowel_ratio: None,
+            min_distinct_initials: 0,
+            min_pool_size: DEFAULT_MIN_POOL_SIZE,
+            homophones: vec![],
+            max_retries: DEFAULT_MAX_RETRIES,
+            require_noun: false,
+            min_words: 0,
+            phrase_determiner: false,
+            pos_weights: HashMap::new(),
+            any_word_mode: AnyWordMode::default(),
+            selection_mode: SelectionMode::default(),
+            case: None,
+            boundary_case: None,
+            output_case: OutputCase::default(),
+            ascii_fold: false,
+            unicode_normalization: None,
+            digit_grouping: None,
+            hex_uppercase: false,
+            terminal_punctuation: false,
+            require_nonempty: false,
+            random_caps: false,
+            pos_labels: false,
+            leet: false,
+            leet_map: DEFAULT_LEET_MAP.iter().copied().collect(),
+            seed: None,
+            db_path: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FluentCodesConfig, DEFAULT_JOINER, DEFAULT_MAX_LENGTH, DEFAULT_MIN_LENGTH};
+
+    #[test]
+    fn default_constants() {
+        let config = FluentCodesConfig::default();
+        assert_eq!(config.joiner, DEFAULT_JOINER);
+        assert_eq!(config.min_length, DEFAULT_MIN_LENGTH);
+        assert_eq!(config.max_length, DEFAULT_MAX_LENGTH);
+        assert_eq!(
+            (DEFAULT_JOINER, DEFAULT_MIN_LENGTH, DEFAULT_MAX_LENGTH),
+            ("-", 6, 6)
+        );
+    }
+}
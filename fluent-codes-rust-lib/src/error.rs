@@ -0,0 +1,146 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::WordType;
+
+/// Errors returned by fallible FluentCodes operations
+#[derive(Debug)]
+pub enum FluentCodesError {
+    /// The underlying database query failed
+    Database(rusqlite::Error),
+    /// A token could not be decoded
+    InvalidToken(String),
+    /// No word of the type has a length in the requested range
+    NoWordsInRange(WordType, usize, usize),
+    /// Every provider of a chain failed, with the error of each
+    AllProvidersFailed(Vec<FluentCodesError>),
+    /// The table name does not belong to a word type
+    UnknownTable(String),
+    /// The table name is not a plain SQL identifier and cannot be queried safely
+    InvalidTableName(String),
+    /// The word type has no words at all
+    EmptyTable(WordType),
+    /// The provider does not support the operation
+    Unsupported(&'static str),
+    /// No acceptable word of the type was found within the given number of retries
+    RetryLimitExceeded(WordType, usize),
+    /// A structure pattern could not be parsed
+    InvalidPattern(String),
+    /// No code satisfying the constraints was generated within the given number of
+    /// regenerations
+    RegenerationLimitExceeded(usize),
+    /// No word type can be drawn for a randomly chosen word
+    NoWordTypes,
+    /// The word type has only the given number of words in range, fewer than the minimum
+    PoolTooSmall(WordType, u64, usize),
+    /// The generated code is not accepted by the generator's own validation
+    ValidationFailed(String),
+    /// No code of the target length, within the tolerance, can be planned
+    LengthUnreachable(usize, usize),
+    /// No component was chained while a non-empty code is required
+    EmptyCode,
+    /// The length range from the first to the second length is empty
+    EmptyLengthRange(usize, usize),
+    /// A digit or hexadecimal block of zero width was chained
+    ZeroWidth,
+    /// A block of the first number of digits was chained, wider than the second, the
+    /// widest its value fits in
+    WidthTooLarge(usize, usize),
+    /// The value range of a number from the first to the second value is empty
+    EmptyValueRange(u64, u64),
+}
+
+impl fmt::Display for FluentCodesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FluentCodesError::Database(err) => write!(f, "database error: {}", err),
+            FluentCodesError::InvalidToken(token) => write!(f, "invalid token: {}", token),
+            FluentCodesError::NoWordsInRange(word_type, min, max) => write!(
+                f,
+                "no {} words with a length between {} and {}",
+                word_type.table(),
+                min,
+                max
+            ),
+            FluentCodesError::AllProvidersFailed(errors) => {
+                let errors: Vec<String> = errors.iter().map(|err| err.to_string()).collect();
+                write!(f, "all providers failed: {}", errors.join("; "))
+            }
+            FluentCodesError::UnknownTable(table) => write!(f, "unknown table: {}", table),
+            FluentCodesError::InvalidTableName(table) => {
+                write!(f, "invalid table name: {:?}", table)
+            }
+            FluentCodesError::EmptyTable(word_type) => {
+                write!(f, "no {} words", word_type.table())
+            }
+            FluentCodesError::Unsupported(operation) => {
+                write!(f, "{} is not supported by this provider", operation)
+            }
+            FluentCodesError::RetryLimitExceeded(word_type, retries) => write!(
+                f,
+                "no acceptable {} word after {} retries",
+                word_type.table(),
+                retries
+            ),
+            FluentCodesError::InvalidPattern(pattern) => write!(f, "invalid pattern: {}", pattern),
+            FluentCodesError::RegenerationLimitExceeded(regenerations) => write!(
+                f,
+                "no code satisfying the constraints after {} regenerations",
+                regenerations
+            ),
+            FluentCodesError::NoWordTypes => write!(f, "no word type can be drawn"),
+            FluentCodesError::PoolTooSmall(word_type, count, min) => write!(
+                f,
+                "only {} {} words in range, fewer than {}",
+                count,
+                word_type.table(),
+                min
+            ),
+            FluentCodesError::ValidationFailed(code) => {
+                write!(f, "generated code fails validation: {}", code)
+            }
+            FluentCodesError::LengthUnreachable(target, tolerance) => write!(
+                f,
+                "no code of {} characters, give or take {}, can be planned",
+                target, tolerance
+            ),
+            FluentCodesError::EmptyCode => write!(f, "no component was chained"),
+            FluentCodesError::EmptyLengthRange(min, max) => {
+                write!(f, "empty length range {}..={}", min, max)
+            }
+            FluentCodesError::ZeroWidth => write!(f, "a digit block needs at least one digit"),
+            FluentCodesError::WidthTooLarge(width, max) => {
+                write!(f, "a block of {} digits is wider than {}", width, max)
+            }
+            FluentCodesError::EmptyValueRange(start, end) => {
+                write!(f, "empty value range {}..={}", start, end)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FluentCodesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FluentCodesError::Database(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for FluentCodesError {
+    fn from(err: rusqlite::Error) -> Self {
+        FluentCodesError::Database(err)
+    }
+}
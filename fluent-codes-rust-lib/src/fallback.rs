@@ -0,0 +1,109 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal word lists compiled into the binary, used when the database is unavailable.
+
+use crate::WordType;
+
+const ADJECTIVE: &[&str] = &[
+    "calm", "bold", "warm", "quick", "brave", "happy", "sunny", "quiet", "bright", "fluffy",
+    "gentle", "silver", "golden", "clever", "steady", "frosty", "mellow", "nimble", "polite",
+    "purple", "simple", "smooth", "modest", "lively", "curious", "radiant", "amazing",
+];
+const ADPOSITION: &[&str] = &[
+    "at", "by", "in", "of", "on", "to", "up", "for", "off", "via", "from", "into", "near", "onto",
+    "over", "with", "above", "after", "below", "under", "across", "around", "before", "behind",
+    "beside", "beyond", "during", "inside", "toward", "within", "between", "through",
+];
+const ADVERB: &[&str] = &[
+    "now", "also", "soon", "very", "again", "often", "quite", "early", "always", "almost",
+    "really", "slowly", "loudly", "gladly", "calmly", "kindly", "boldly", "rarely", "simply",
+    "wisely", "warmly", "openly", "barely", "deeply", "quickly", "happily", "brightly",
+];
+const AUXILIARY: &[&str] = &[
+    "am", "be", "do", "is", "are", "can", "did", "had", "has", "may", "was", "been", "does",
+    "have", "must", "were", "will", "being", "could", "might", "shall", "would", "should",
+    "having", "become", "cannot", "seemed", "remain",
+];
+const COORDINATING_CONJUNCTION: &[&str] = &[
+    "and", "but", "nor", "for", "yet", "so", "or", "plus", "then", "both", "minus", "either",
+    "versus", "rather", "though", "neither",
+];
+const DETERMINER: &[&str] = &[
+    "a", "an", "the", "all", "any", "few", "her", "his", "its", "our", "both", "each", "much",
+    "some", "that", "this", "what", "their", "these", "those", "which", "either", "enough",
+    "little", "double", "plenty", "another", "several",
+];
+const INTERJECTION: &[&str] = &[
+    "ah", "oh", "wow", "hey", "yay", "oops", "ouch", "whoa", "hello", "hooray", "bravo", "thanks",
+    "please", "anyway", "anyhow", "cheers", "alas", "eureka", "gosh", "phew", "yippee", "howdy",
+    "hurray", "ahchoo", "bingo", "voila",
+];
+const NOUN: &[&str] = &[
+    "fox", "owl", "sun", "tree", "lake", "moon", "river", "cloud", "stone", "piano", "rocket",
+    "garden", "meadow", "planet", "forest", "bridge", "candle", "island", "lantern", "falcon",
+    "ribbon", "pebble", "harbor", "window", "anchor", "comet", "mountain", "teapot",
+];
+const PARTICLE: &[&str] = &[
+    "to", "up", "not", "off", "out", "away", "back", "down", "over", "along", "about", "toward",
+    "onward", "upward", "around", "aside", "forth",
+];
+const PRONOUN: &[&str] = &[
+    "i", "me", "we", "us", "he", "it", "she", "her", "him", "you", "they", "them", "mine", "ours",
+    "yours", "whom", "itself", "myself", "nobody", "anyone", "theirs", "others", "anybody",
+    "someone", "himself", "herself", "everyone",
+];
+const PROPER_NOUN: &[&str] = &[
+    "anna", "oslo", "rome", "mars", "nile", "paris", "tokyo", "kenya", "venus", "alice", "london",
+    "sydney", "dublin", "monaco", "berlin", "madrid", "athens", "nelson", "oliver", "sophia",
+    "amelia", "jupiter", "everest", "sahara", "amazon", "saturn", "neptune",
+];
+const PUNCTUATION: &[&str] = &[
+    "dot", "dash", "comma", "colon", "quote", "slash", "tilde", "caret", "period", "hyphen",
+    "commas", "colons", "quotes", "braces", "carets", "tildes", "dashes", "bracket", "ellipsis",
+    "stroke",
+];
+const SUBORDINATING_CONJUNCTION: &[&str] = &[
+    "as", "if", "so", "once", "than", "that", "till", "when", "while", "since", "until", "where",
+    "after", "unless", "though", "before", "whilst", "except", "albeit", "whereas", "because",
+    "although", "whether", "whenever",
+];
+const SYMBOL: &[&str] = &[
+    "x", "lb", "kg", "usd", "eur", "gbp", "euro", "plus", "pound", "minus", "cents", "equals",
+    "dollar", "degree", "micron", "sterling", "percent",
+];
+const VERB: &[&str] = &[
+    "run", "fly", "jump", "sing", "swim", "climb", "dance", "paint", "build", "dream", "wander",
+    "gather", "listen", "travel", "sparkle", "create", "follow", "wonder", "glide", "bounce",
+    "whistle", "launch", "settle", "admire", "juggle", "invent", "explore",
+];
+
+/// Fallback words of the given type
+pub(crate) fn words(word_type: WordType) -> &'static [&'static str] {
+    match word_type {
+        WordType::Adjective => ADJECTIVE,
+        WordType::Adposition => ADPOSITION,
+        WordType::Adverb => ADVERB,
+        WordType::Auxiliary => AUXILIARY,
+        WordType::CoordinatingConjunction => COORDINATING_CONJUNCTION,
+        WordType::Determiner => DETERMINER,
+        WordType::Interjection => INTERJECTION,
+        WordType::Noun => NOUN,
+        WordType::Particle => PARTICLE,
+        WordType::Pronoun => PRONOUN,
+        WordType::ProperNoun => PROPER_NOUN,
+        WordType::Punctuation => PUNCTUATION,
+        WordType::SubordinatingConjunction => SUBORDINATING_CONJUNCTION,
+        WordType::Symbol => SYMBOL,
+        WordType::Verb => VERB,
+    }
+}
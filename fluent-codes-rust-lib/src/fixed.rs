@@ -0,0 +1,65 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Codes of a structure fixed at compile time.
+
+use rand::{Rng, SeedableRng};
+
+use crate::{fallback, CodeRng, WordType};
+
+/// Generator of codes with `N` words of a structure known at compile time, selected from
+/// the word lists compiled into the binary, so no database is opened and no plan is
+/// allocated
+#[derive(Debug, Clone)]
+pub struct FixedCode<const N: usize> {
+    structure: [WordType; N],
+    rng: CodeRng,
+}
+
+impl<const N: usize> FixedCode<N> {
+    /// Creates a generator selecting one word of each type of `structure`, in order
+    pub fn new(structure: [WordType; N]) -> Self {
+        FixedCode {
+            structure,
+            rng: CodeRng::from_entropy(),
+        }
+    }
+    /// Seeds the random number generator, so the same seed always produces the same codes
+    pub fn with_seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = CodeRng::seed_from_u64(seed);
+        self
+    }
+    /// Selects the words of a new code
+    pub fn generate(&mut self) -> [String; N] {
+        let rng = &mut self.rng;
+        self.structure.map(|word_type| {
+            let words = fallback::words(word_type);
+            words[rng.gen_range(0..words.len())].to_string()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fallback, FixedCode, WordType};
+
+    #[test]
+    fn three_component_code() {
+        let structure = [WordType::Adjective, WordType::Noun, WordType::Verb];
+        let mut codes = FixedCode::new(structure);
+        let code: [String; 3] = codes.with_seed(9).generate();
+        for (word, word_type) in code.iter().zip(structure) {
+            assert!(fallback::words(word_type).contains(&word.as_str()));
+        }
+        assert_eq!(FixedCode::new(structure).with_seed(9).generate(), code);
+    }
+}
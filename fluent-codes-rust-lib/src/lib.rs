@@ -62,168 +62,2709 @@
 //! ### License
 //!
 //! http://www.apache.org/licenses/LICENSE-2.0
-use rand::Rng;
-use rusqlite::{Connection, Result};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::Instant;
+
+use rand::distributions::{Distribution, WeightedIndex};
+#[cfg(feature = "small-rng")]
+use rand::rngs::SmallRng;
+#[cfg(not(feature = "small-rng"))]
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use rust_embed::RustEmbed;
 
+pub use any_word_mode::AnyWordMode;
+pub use bench::BenchStats;
+pub use case::Case;
+pub use component::Component;
+pub use config::FluentCodesConfig;
+pub use error::FluentCodesError;
+pub use fixed::FixedCode;
+pub use normalize::Form;
+pub use output_case::OutputCase;
+pub use provider::{ChainProvider, DbSource, InMemoryProvider, SqliteProvider, WordProvider};
+pub use selection_mode::SelectionMode;
+pub use word_type::WordType;
+
+use token::Chunk;
+
+mod any_word_mode;
+mod bench;
+mod case;
+mod component;
+mod config;
+mod error;
+mod fallback;
+mod fixed;
+mod fold;
+mod normalize;
+mod output_case;
+mod provider;
+mod selection_mode;
+mod token;
+mod word_type;
+
 #[derive(RustEmbed)]
 #[folder = "db"]
 struct Asset;
 
-/// Implementation struct
+/// Where a generated component came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    /// Word type of a selected word
+    Word(WordType),
+    /// Width and value of a digit block
+    Digits(usize, u64),
+    /// Block of hexadecimal digits, lower case as selected
+    Hex,
+    /// Fixed text given by the caller
+    Literal,
+    /// Time of selection, see [`FluentCodes::with_timestamp_base36`]
+    Timestamp,
+}
+
+impl Source {
+    /// Whether the component is a digit block, number, hexadecimal block or timestamp, moved
+    /// together by [`FluentCodes::with_digits_last`]
+    fn is_numeric(self) -> bool {
+        match self {
+            Source::Digits(..) | Source::Hex | Source::Timestamp => true,
+            Source::Word(_) | Source::Literal => false,
+        }
+    }
+}
+
+/// A generated component and its source
 #[derive(Debug)]
+struct Part {
+    text: String,
+    source: Source,
+    /// Joiner drawn for the gap before the part, overriding the fixed joiner
+    joiner: Option<String>,
+    /// Letters of a word upper cased by [`FluentCodes::with_random_caps`], empty when unset
+    caps: Vec<bool>,
+}
+
+/// Components selected from the plan so far and the state selecting them draws on
+struct Selection {
+    words: Vec<Part>,
+    /// Number of planned components selected, or failed to select, so far
+    selected: usize,
+    /// Number of parts each selected component produced, one entry per component
+    spans: Vec<usize>,
+    error: Option<FluentCodesError>,
+    /// Whether digit blocks are displayed first, drawn per code by
+    /// [`FluentCodes::with_random_layout`]
+    digits_first: bool,
+    rng: CodeRng,
+}
+
+impl Selection {
+    fn new(rng: CodeRng) -> Selection {
+        Selection {
+            words: vec![],
+            selected: 0,
+            spans: vec![],
+            error: None,
+            digits_first: false,
+            rng,
+        }
+    }
+    fn fail(&mut self, err: FluentCodesError) {
+        self.error.get_or_insert(err);
+    }
+    /// Index of the first part of the planned component at `index`
+    fn offset(&self, index: usize) -> usize {
+        self.spans.iter().take(index).sum()
+    }
+    /// Drops the selected components and any pending error, so the plan is selected anew
+    fn reset(&mut self) {
+        self.words.clear();
+        self.selected = 0;
+        self.spans.clear();
+        self.error = None;
+        self.digits_first = false;
+    }
+}
+
+/// An invalid setting or component, reported by every [`FluentCodes::build`] until it is
+/// dropped, unlike selection errors which are cleared on regeneration
+#[derive(Debug, Clone, Copy)]
+enum Misconfiguration {
+    /// A digit or hexadecimal block of zero width was chained
+    ZeroWidth,
+    /// An empty length range was set
+    EmptyLengthRange(usize, usize),
+    /// A block wider than the widest of its kind was chained
+    WidthTooLarge(usize, usize),
+    /// A number of an empty value range was chained
+    EmptyValueRange(u64, u64),
+}
+
+impl Misconfiguration {
+    fn error(self) -> FluentCodesError {
+        match self {
+            Misconfiguration::ZeroWidth => FluentCodesError::ZeroWidth,
+            Misconfiguration::EmptyLengthRange(start, end) => {
+                FluentCodesError::EmptyLengthRange(start, end)
+            }
+            Misconfiguration::WidthTooLarge(width, max) => {
+                FluentCodesError::WidthTooLarge(width, max)
+            }
+            Misconfiguration::EmptyValueRange(start, end) => {
+                FluentCodesError::EmptyValueRange(start, end)
+            }
+        }
+    }
+    /// Whether it concerns a chained component rather than a setting, so it is dropped
+    /// with the components by [`FluentCodes::clear`]
+    fn is_chained(self) -> bool {
+        match self {
+            Misconfiguration::ZeroWidth
+            | Misconfiguration::WidthTooLarge(..)
+            | Misconfiguration::EmptyValueRange(..) => true,
+            Misconfiguration::EmptyLengthRange(..) => false,
+        }
+    }
+}
+
+/// Joiner placed between the components of a code
+pub const DEFAULT_JOINER: &str = "-";
+/// Shortest word length selected by default
+pub const DEFAULT_MIN_LENGTH: usize = 6;
+/// Longest word length selected by default
+pub const DEFAULT_MAX_LENGTH: usize = 6;
+/// Tables of the bundled database, one per [`WordType`] in [`WordType::ALL`] order
+pub const DEFAULT_TABLES: &[&str] = &[
+    "adj", "adp", "adv", "aux", "cconj", "det", "intj", "noun", "part", "pron", "propn", "punct",
+    "sconj", "sym", "verb",
+];
+/// Fewest in-range words of a planned word type [`FluentCodes::check_feasibility`] accepts
+pub const DEFAULT_MIN_POOL_SIZE: usize = 10;
+/// Letters replaced by [`FluentCodes::with_leet`] unless [`FluentCodes::with_leet_map`] is set
+pub const DEFAULT_LEET_MAP: &[(char, char)] = &[('a', '4'), ('e', '3'), ('o', '0'), ('s', '5')];
+/// Number of times a constrained selection re-samples before giving up
+pub const DEFAULT_MAX_RETRIES: usize = 100;
+
+/// Widest digit block, the largest width whose values fit in a `u64`
+pub const MAX_DIGITS: usize = 19;
+/// Widest hexadecimal block, the number of hexadecimal digits of a `u64`
+pub const MAX_HEX_DIGITS: usize = 16;
+
+type WordFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+type SharedProvider = Arc<Mutex<Box<dyn WordProvider>>>;
+type Clock = Arc<dyn Fn() -> std::time::SystemTime + Send + Sync>;
+/// Random number generator of word offsets, digits and joiners, the lighter `SmallRng`
+/// with the `small-rng` feature
+#[cfg(not(feature = "small-rng"))]
+type CodeRng = StdRng;
+#[cfg(feature = "small-rng")]
+type CodeRng = SmallRng;
+
+/// Implementation struct
+///
+/// Chained components are only planned; words, digits and joiners are selected by the
+/// first terminal call, such as [`FluentCodes::build`] or displaying the code, and kept
+/// until the plan is cleared. Selection errors are kept until [`FluentCodes::build`]
+/// reports them.
+///
+/// # Panics
+///
+/// Displaying the code, with `to_string`, `format!` or [`FluentCodes::into_parts`], panics
+/// whenever [`FluentCodes::build`] would fail: on an invalid component or setting, such as
+/// a zero width digit block or an empty length range, and on a word that could not be
+/// selected. Call [`FluentCodes::build`] to handle these errors instead.
 pub struct FluentCodes {
-    words: Vec<String>,
-    connection: Option<Connection>,
+    plan: Vec<Component>,
+    /// Joiners set by [`FluentCodes::switch_joiner`] with the position of the first
+    /// component they precede
+    joiner_switches: Vec<(usize, String)>,
+    selection: Mutex<Selection>,
+    misconfiguration: Option<Misconfiguration>,
+    provider: SharedProvider,
     joiner: String,
-    min_length: i32,
-    max_length: i32,
+    final_joiner: Option<String>,
+    number_separator: Option<String>,
+    joiner_escape: Option<char>,
+    digits_last: bool,
+    random_layout: bool,
+    min_length: usize,
+    max_length: usize,
+    default_length_map: HashMap<WordType, (usize, usize)>,
+    auto_clamp: bool,
+    word_filter: Option<WordFilter>,
+    joiner_safe: bool,
+    symbol_joiners: bool,
+    weighted_joiners: Vec<(String, u32)>,
+    shuffled_digits: bool,
+    no_leading_zero: bool,
+    min_vowel_ratio: Option<f64>,
+    min_distinct_initials: usize,
+    min_pool_size: usize,
+    homophones: Vec<Vec<String>>,
+    max_retries: usize,
+    require_noun: bool,
+    min_words: usize,
+    phrase_determiner: bool,
+    pos_weights: HashMap<WordType, u32>,
+    any_word_mode: AnyWordMode,
+    selection_mode: SelectionMode,
+    /// Cached in-range word counts by word type and length range
+    counts: Mutex<HashMap<(WordType, usize, usize), u64>>,
+    case: Option<Case>,
+    boundary_case: Option<Case>,
+    output_case: OutputCase,
+    ascii_fold: bool,
+    unicode_normalization: Option<Form>,
+    digit_grouping: Option<(usize, char)>,
+    hex_uppercase: bool,
+    terminal_punctuation: bool,
+    require_nonempty: bool,
+    random_caps: bool,
+    pos_labels: bool,
+    leet: bool,
+    leet_map: HashMap<char, char>,
+    clock: Clock,
 }
 
 /// Default trait implemented  for FluentCodes struct
 impl Default for FluentCodes {
     fn default() -> Self {
-        FluentCodes {
-            words: vec![],
-            connection: Option::None,
-            joiner: "-".to_string(),
-            min_length: 6,
-            max_length: 6,
+        FluentCodes::from_config(&FluentCodesConfig::default())
+    }
+}
+
+/// Debug trait implemented  for FluentCodes struct
+impl fmt::Debug for FluentCodes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let selection = lock(&self.selection);
+        f.debug_struct("FluentCodes")
+            .field("plan", &self.plan)
+            .field("words", &selection.words)
+            .field("error", &selection.error)
+            .field("joiner", &self.joiner)
+            .field("min_length", &self.min_length)
+            .field("max_length", &self.max_length)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the characters of a code displayed with [`FluentCodes::with_joiner_escape`] and
+/// whether each was escaped, removing the escape characters
+fn unescaped(code: &str, escape: Option<char>) -> Vec<(char, bool)> {
+    let mut units = vec![];
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        match escape {
+            Some(escape) if c == escape => units.extend(chars.next().map(|c| (c, true))),
+            _ => units.push((c, false)),
+        }
+    }
+    units
+}
+
+/// A joiner that can be displayed between two parts of a code
+enum Gap {
+    /// Fixed text
+    Text(String),
+    /// Any single symbol, drawn by [`FluentCodes::with_symbol_joiners`]
+    Symbol,
+}
+
+impl Gap {
+    /// Where the gap ends when it starts at `pos` of `units`, which only match unescaped
+    fn end(&self, units: &[(char, bool)], pos: usize) -> Option<usize> {
+        match self {
+            Gap::Text(text) => {
+                let mut end = pos;
+                for c in text.chars() {
+                    match units.get(end) {
+                        Some((unit, false)) if *unit == c => end += 1,
+                        _ => return None,
+                    }
+                }
+                Some(end)
+            }
+            Gap::Symbol => match units.get(pos) {
+                Some((c, false)) if !c.is_alphanumeric() && !c.is_whitespace() => Some(pos + 1),
+                _ => None,
+            },
         }
     }
 }
 
-/// ToString trait implemented  for FluentCodes struct
-impl ToString for FluentCodes {
-    fn to_string(&self) -> String {
-        return self.words.join(&self.joiner);
+/// Whether `units` from `pos` split into the parts of `components` from `index`, each
+/// preceded by one of its `gaps` and taken by `accept`. Parts cannot hold an unescaped
+/// `escaped` joiner.
+fn split_parts(
+    units: &[(char, bool)],
+    components: &[&Component],
+    gaps: &[Vec<Gap>],
+    escaped: Option<&Gap>,
+    (index, pos): (usize, usize),
+    accept: &mut dyn FnMut(&Component, &str) -> Result<bool, FluentCodesError>,
+) -> Result<bool, FluentCodesError> {
+    let Some(component) = components.get(index) else {
+        return Ok(pos == units.len());
+    };
+    let starts: Vec<usize> = match index {
+        0 => vec![pos],
+        _ => gaps[index]
+            .iter()
+            .filter_map(|gap| gap.end(units, pos))
+            .collect(),
+    };
+    for start in starts {
+        for end in start..=units.len() {
+            let ends_part = match gaps.get(index + 1) {
+                Some(next) => next.iter().any(|gap| gap.end(units, end).is_some()),
+                None => end == units.len(),
+            };
+            if !ends_part {
+                continue;
+            }
+            let joined = escaped.is_some_and(|joiner| {
+                (start..end).any(|from| joiner.end(&units[..end], from).is_some())
+            });
+            if joined {
+                break;
+            }
+            let part: String = units[start..end].iter().map(|(c, _)| c).collect();
+            if accept(component, &part)?
+                && split_parts(units, components, gaps, escaped, (index + 1, end), accept)?
+            {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Display trait implemented  for FluentCodes struct
+impl fmt::Display for FluentCodes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let selection = self.checked_selection();
+        match self.output_case {
+            OutputCase::AsIs => self.write_code(&selection, f),
+            case => {
+                let mut code = String::new();
+                self.write_code(&selection, &mut code)?;
+                f.write_str(&case.apply(&code))
+            }
+        }
+    }
+}
+
+impl FluentCodes {
+    /// Writes the code of `selection` as assembled, before [`FluentCodes::with_output_case`]
+    fn write_code(&self, selection: &Selection, f: &mut impl fmt::Write) -> fmt::Result {
+        let words = self.styled(selection);
+        let last = words.len().saturating_sub(1);
+        for (index, (word, part)) in words.iter().zip(self.assembled(selection)).enumerate() {
+            if index > 0 {
+                let joiner = match (&self.number_separator, &self.final_joiner, &part.joiner) {
+                    (Some(sep), _, _) if matches!(part.source, Source::Digits(..)) => sep,
+                    (_, Some(final_joiner), _) if index == last => final_joiner,
+                    (_, _, Some(symbol)) => symbol,
+                    _ => self.joiner_at(selection, index),
+                };
+                f.write_str(joiner)?;
+            }
+            match self.joiner_escape {
+                Some(escape) if !self.joiner.is_empty() => {
+                    let escaped = word
+                        .replace(escape, &format!("{0}{0}", escape))
+                        .replace(&self.joiner, &format!("{}{}", escape, self.joiner));
+                    f.write_str(&escaped)?;
+                }
+                _ => f.write_str(word)?,
+            }
+            if let (true, Source::Word(word_type)) = (self.pos_labels, part.source) {
+                write!(f, "({})", word_type.table().to_uppercase())?;
+            }
+        }
+        if self.terminal_punctuation {
+            f.write_str(".")?;
+        }
+        Ok(())
     }
 }
 
 /// code generation builder methods for FluentCodes struct
 impl FluentCodes {
+    /// Creates a generator over the bundled database, looked up like
+    /// [`SqliteProvider::default`] but opened immediately, so a database that cannot be read
+    /// is reported here rather than on the first selection. [`FluentCodes::default`] opens
+    /// it lazily and falls back to the embedded word list.
+    pub fn new() -> Result<FluentCodes, FluentCodesError> {
+        let provider = SqliteProvider::default().connect()?;
+        let mut codes = FluentCodes::default();
+        codes.with_provider(provider);
+        Ok(codes)
+    }
+    /// Creates a generator over the database at `path`, opened immediately like
+    /// [`FluentCodes::new`]
+    pub fn open(path: impl AsRef<Path>) -> Result<FluentCodes, FluentCodesError> {
+        let provider = SqliteProvider::open(path)?;
+        let mut codes = FluentCodes::default();
+        codes.with_provider(provider);
+        Ok(codes)
+    }
+    /// Creates a generator from `config` and generates its structure
+    pub fn from_config(config: &FluentCodesConfig) -> FluentCodes {
+        let provider = match &config.db_path {
+            Some(path) => SqliteProvider::new(path),
+            None => SqliteProvider::default(),
+        };
+        let mut codes = FluentCodes {
+            plan: vec![],
+            joiner_switches: vec![],
+            misconfiguration: None,
+            selection: Mutex::new(Selection::new(match config.seed {
+                Some(seed) => CodeRng::seed_from_u64(seed),
+                None => CodeRng::from_entropy(),
+            })),
+            provider: Arc::new(Mutex::new(Box::new(provider))),
+            joiner: config.joiner.clone(),
+            final_joiner: config.final_joiner.clone(),
+            number_separator: config.number_separator.clone(),
+            joiner_escape: config.joiner_escape,
+            digits_last: config.digits_last,
+            random_layout: config.random_layout,
+            min_length: config.min_length,
+            max_length: config.max_length,
+            default_length_map: config.default_length_map.clone(),
+            auto_clamp: config.auto_clamp,
+            word_filter: None,
+            joiner_safe: config.joiner_safe,
+            symbol_joiners: config.symbol_joiners,
+            weighted_joiners: config.weighted_joiners.clone(),
+            shuffled_digits: config.shuffled_digits,
+            no_leading_zero: config.no_leading_zero,
+            min_vowel_ratio: config.min_vowel_ratio,
+            min_distinct_initials: config.min_distinct_initials,
+            min_pool_size: config.min_pool_size,
+            homophones: config.homophones.clone(),
+            max_retries: config.max_retries,
+            require_noun: config.require_noun,
+            min_words: config.min_words,
+            phrase_determiner: config.phrase_determiner,
+            pos_weights: config.pos_weights.clone(),
+            any_word_mode: config.any_word_mode,
+            selection_mode: config.selection_mode,
+            counts: Mutex::new(HashMap::new()),
+            case: config.case,
+            boundary_case: config.boundary_case,
+            output_case: config.output_case,
+            ascii_fold: config.ascii_fold,
+            unicode_normalization: config.unicode_normalization,
+            digit_grouping: config.digit_grouping,
+            hex_uppercase: config.hex_uppercase,
+            terminal_punctuation: config.terminal_punctuation,
+            require_nonempty: config.require_nonempty,
+            random_caps: config.random_caps,
+            pos_labels: config.pos_labels,
+            leet: config.leet,
+            leet_map: config.leet_map.clone(),
+            clock: Arc::new(std::time::SystemTime::now),
+        };
+        codes.with_capacity_hint(config.structure.len());
+        for component in &config.structure {
+            codes.chain(component.clone());
+        }
+        codes
+    }
+    /// Creates a generator with the same configuration and word source, sharing the
+    /// source's connection, but no components and its own random number generator
+    pub fn clone_config(&self) -> FluentCodes {
+        let mut codes = FluentCodes::from_config(&self.config());
+        codes.misconfiguration = self.misconfiguration.filter(|it| !it.is_chained());
+        codes.provider = Arc::clone(&self.provider);
+        codes.word_filter = self.word_filter.clone();
+        codes.counts = Mutex::new(lock(&self.counts).clone());
+        codes.clock = Arc::clone(&self.clock);
+        codes
+    }
+    /// Returns the configuration without structure, seed or database path
+    fn config(&self) -> FluentCodesConfig {
+        FluentCodesConfig {
+            structure: vec![],
+            joiner: self.joiner.clone(),
+            final_joiner: self.final_joiner.clone(),
+            number_separator: self.number_separator.clone(),
+            joiner_escape: self.joiner_escape,
+            digits_last: self.digits_last,
+            random_layout: self.random_layout,
+            min_length: self.min_length,
+            max_length: self.max_length,
+            default_length_map: self.default_length_map.clone(),
+            auto_clamp: self.auto_clamp,
+            joiner_safe: self.joiner_safe,
+            symbol_joiners: self.symbol_joiners,
+            weighted_joiners: self.weighted_joiners.clone(),
+            shuffled_digits: self.shuffled_digits,
+            no_leading_zero: self.no_leading_zero,
+            min_vowel_ratio: self.min_vowel_ratio,
+            min_distinct_initials: self.min_distinct_initials,
+            min_pool_size: self.min_pool_size,
+            homophones: self.homophones.clone(),
+            max_retries: self.max_retries,
+            require_noun: self.require_noun,
+            min_words: self.min_words,
+            phrase_determiner: self.phrase_determiner,
+            pos_weights: self.pos_weights.clone(),
+            any_word_mode: self.any_word_mode,
+            selection_mode: self.selection_mode,
+            case: self.case,
+            boundary_case: self.boundary_case,
+            output_case: self.output_case,
+            ascii_fold: self.ascii_fold,
+            unicode_normalization: self.unicode_normalization,
+            digit_grouping: self.digit_grouping,
+            hex_uppercase: self.hex_uppercase,
+            terminal_punctuation: self.terminal_punctuation,
+            require_nonempty: self.require_nonempty,
+            random_caps: self.random_caps,
+            pos_labels: self.pos_labels,
+            leet: self.leet,
+            leet_map: self.leet_map.clone(),
+            seed: None,
+            db_path: None,
+        }
+    }
+    /// Selects the components of `structure` in order, fetching runs of words of one type
+    /// from the word source at once
+    fn generate(&self, selection: &mut Selection, structure: &[Component]) {
+        let mut index = 0;
+        while let Some(component) = structure.get(index) {
+            index += 1;
+            let start = selection.words.len();
+            let mut components = 1;
+            match component {
+                Component::Word(word_type) => {
+                    let run = match self.selection_mode {
+                        SelectionMode::Random => structure[index..]
+                            .iter()
+                            .take_while(|next| *next == component)
+                            .count(),
+                        _ => 0,
+                    };
+                    index += run;
+                    components += run;
+                    match run {
+                        0 => self.select_word_now(selection, *word_type),
+                        _ => self.select_words(selection, *word_type, run + 1),
+                    }
+                }
+                Component::AnyWord => match self.draw_word_type(selection, false) {
+                    Ok(word_type) => self.select_word_now(selection, word_type),
+                    Err(err) => selection.fail(err),
+                },
+                Component::RandomStructure(count) => match self.draw_structure(selection, *count) {
+                    Ok(word_types) => {
+                        for word_type in word_types {
+                            self.select_word_now(selection, word_type);
+                        }
+                    }
+                    Err(err) => selection.fail(err),
+                },
+                Component::FitLength(target, tolerance) => {
+                    self.select_fitted(selection, *target, *tolerance)
+                }
+                Component::Digits(_) | Component::Hex(_) | Component::Number(_) => {
+                    if let Some((text, source)) = self.draw_numeric(selection, component) {
+                        self.push(selection, text, source);
+                    }
+                }
+                Component::DrawnDigits(width, value) => {
+                    let digits = format!("{:0width$}", value, width = *width);
+                    self.push(selection, digits, Source::Digits(*width, *value));
+                }
+                Component::Literal(text) => self.push(selection, text.clone(), Source::Literal),
+                Component::Timestamp => {
+                    let text = self.timestamp_base36();
+                    self.push(selection, text, Source::Timestamp);
+                }
+            }
+            let parts = selection.words.len() - start;
+            match components {
+                1 => selection.spans.push(parts),
+                _ => selection
+                    .spans
+                    .extend((0..components).map(|component| usize::from(component < parts))),
+            }
+        }
+    }
+    /// Selects the nouns and the digit block planned by [`FluentCodes::for_length`]
+    fn select_fitted(&self, selection: &mut Selection, target: usize, tolerance: usize) {
+        let (lengths, digits) = match self.fit_length(target, tolerance) {
+            Ok(fit) => fit,
+            Err(err) => return selection.fail(err),
+        };
+        for length in lengths {
+            match self.pick_word(selection, WordType::Noun, (length, length)) {
+                Ok(text) => self.push(selection, text, Source::Word(WordType::Noun)),
+                Err(err) => return selection.fail(err),
+            }
+        }
+        if digits > 0 {
+            let (text, value) = self.draw_digits(selection, digits);
+            self.push(selection, text, Source::Digits(digits, value));
+        }
+    }
+    /// Draws the text of a digit block, hexadecimal block or number, `None` for other
+    /// components
+    fn draw_numeric(
+        &self,
+        selection: &mut Selection,
+        component: &Component,
+    ) -> Option<(String, Source)> {
+        match component {
+            Component::Digits(width) => {
+                let (digits, value) = self.draw_digits(selection, *width);
+                Some((digits, Source::Digits(*width, value)))
+            }
+            Component::Hex(width) => {
+                let value = selection
+                    .rng
+                    .gen::<u64>()
+                    .checked_shr(64 - 4 * *width as u32)
+                    .unwrap_or(0);
+                Some((format!("{:0width$x}", value), Source::Hex))
+            }
+            Component::Number(value_range) => {
+                let width = value_range.end().to_string().len();
+                let value = selection.rng.gen_range(self.number_range(value_range));
+                Some((format!("{:0width$}", value), Source::Digits(width, value)))
+            }
+            _ => None,
+        }
+    }
+    /// Draws the digit blocks, hexadecimal blocks and numbers anew from the generator's
+    /// random number generator, keeping the words, literals and joiners, e.g. after a
+    /// collision of the numeric part. Blocks of [`FluentCodes::six_digits_with`] keep their
+    /// value. Does nothing while a selection error is pending.
+    pub fn reroll_digits(&mut self) -> &mut FluentCodes {
+        let mut selection = self.selected();
+        if selection.error.is_none() {
+            for (index, component) in self.plan.iter().enumerate() {
+                if let Some((text, source)) = self.draw_numeric(&mut selection, component) {
+                    let position = selection.offset(index);
+                    if let Some(part) = selection.words.get_mut(position) {
+                        part.text = text;
+                        part.source = source;
+                    }
+                }
+            }
+        }
+        drop(selection);
+        self
+    }
+    /// Selects the planned components not selected yet and returns the selection
+    fn selected(&self) -> MutexGuard<'_, Selection> {
+        let mut selection = lock(&self.selection);
+        let pending = &self.plan[selection.selected.min(self.plan.len())..];
+        if !pending.is_empty() {
+            if selection.selected == 0 && self.random_layout {
+                selection.digits_first = selection.rng.gen_bool(0.5);
+            }
+            self.generate(&mut selection, pending);
+            selection.selected = self.plan.len();
+        }
+        selection
+    }
+    /// Drops the selected components, so the next terminal call selects the same structure
+    /// anew
+    fn regenerate(&mut self) {
+        lock(&self.selection).reset();
+    }
     pub fn with_joiner(&mut self, joiner: String) -> &mut FluentCodes {
         self.joiner = joiner;
-        return self;
+        self
+    }
+    /// Joins the components chained afterwards with `joiner`, keeping the joiner of the
+    /// earlier gaps; the final joiner, drawn joiners and the number separator still take
+    /// precedence
+    pub fn switch_joiner(&mut self, joiner: String) -> &mut FluentCodes {
+        self.joiner_switches.push((self.plan.len(), joiner));
+        self
+    }
+    /// Joiner of the gap before the component displayed at `index`
+    fn joiner_at(&self, selection: &Selection, index: usize) -> &str {
+        self.joiner_switches
+            .iter()
+            .rev()
+            .find(|(position, _)| selection.offset(*position) <= index)
+            .map_or(&self.joiner, |(_, joiner)| joiner)
     }
-    pub fn with_min_length(&mut self, length: i32) -> &mut FluentCodes {
+    /// Sets the joiner used only between the last two components, e.g. `, and `
+    pub fn with_final_joiner(&mut self, joiner: String) -> &mut FluentCodes {
+        self.final_joiner = Some(joiner);
+        self
+    }
+    /// Sets the joiner used before digit blocks and numbers, e.g. `#` in `fluffy-vacuum#123456`,
+    /// overriding every other joiner in those gaps
+    pub fn with_number_separator(&mut self, separator: String) -> &mut FluentCodes {
+        self.number_separator = Some(separator);
+        self
+    }
+    /// Prefixes occurrences of the joiner and of `escape` inside components with `escape` when
+    /// displayed, so [`FluentCodes::split_escaped`] recovers the components
+    pub fn with_joiner_escape(&mut self, escape: char) -> &mut FluentCodes {
+        self.joiner_escape = Some(escape);
+        self
+    }
+    /// Displays digit blocks, numbers, hexadecimal blocks and timestamps after every other
+    /// component, keeping the relative order of both, for a `words...-digits` shape whatever
+    /// the chain order
+    pub fn with_digits_last(&mut self, digits_last: bool) -> &mut FluentCodes {
+        self.digits_last = digits_last;
+        self
+    }
+    /// Draws for each code whether its numeric components, see
+    /// [`FluentCodes::with_digits_last`], are displayed before or after every other component,
+    /// from the generator's random number generator so seeded codes stay reproducible. Takes
+    /// precedence over [`FluentCodes::with_digits_last`].
+    pub fn with_random_layout(&mut self, random_layout: bool) -> &mut FluentCodes {
+        self.random_layout = random_layout;
+        self
+    }
+    /// Sets the shortest word length selected, negative lengths are rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// fluent_codes_rust::FluentCodes::default().with_min_length(-1);
+    /// ```
+    pub fn with_min_length(&mut self, length: usize) -> &mut FluentCodes {
+        if self.min_length != length {
+            lock(&self.counts).clear();
+        }
         self.min_length = length;
-        return self;
+        self.fix_length_range();
+        self
     }
-    pub fn with_max_length(&mut self, length: i32) -> &mut FluentCodes {
+    /// Sets the longest word length selected
+    pub fn with_max_length(&mut self, length: usize) -> &mut FluentCodes {
+        if self.max_length != length {
+            lock(&self.counts).clear();
+        }
         self.max_length = length;
-        return self;
+        self.fix_length_range();
+        self
+    }
+    /// Sets the shortest and longest word length selected. An empty range leaves the length
+    /// range unchanged and is reported by every [`FluentCodes::build`] as
+    /// [`FluentCodesError::EmptyLengthRange`] until a length is set again.
+    pub fn with_length_range(&mut self, range: RangeInclusive<usize>) -> &mut FluentCodes {
+        if range.is_empty() {
+            self.misconfigure(Misconfiguration::EmptyLengthRange(
+                *range.start(),
+                *range.end(),
+            ));
+            return self;
+        }
+        self.with_min_length(*range.start())
+            .with_max_length(*range.end())
+    }
+    /// Sets the shortest and longest word length of each listed word type, e.g. nouns of 4
+    /// to 8 letters and adjectives of 3 to 6, used instead of the length range set by
+    /// [`FluentCodes::with_length_range`], which still applies to unlisted types
+    pub fn with_default_length_map(
+        &mut self,
+        lengths: HashMap<WordType, (usize, usize)>,
+    ) -> &mut FluentCodes {
+        lock(&self.counts).clear();
+        self.default_length_map = lengths;
+        self
+    }
+    /// Selects words of any length, ignoring the configured length range
+    pub fn without_length_filter(&mut self) -> &mut FluentCodes {
+        self.with_min_length(0).with_max_length(usize::MAX)
+    }
+    /// Sets the shortest word length from an `i32`, treating negative lengths as zero
+    #[deprecated(note = "use with_min_length, which takes a usize")]
+    pub fn with_min_length_i32(&mut self, length: i32) -> &mut FluentCodes {
+        self.with_min_length(length.max(0) as usize)
+    }
+    /// Sets the longest word length from an `i32`, treating negative lengths as zero
+    #[deprecated(note = "use with_max_length, which takes a usize")]
+    pub fn with_max_length_i32(&mut self, length: i32) -> &mut FluentCodes {
+        self.with_max_length(length.max(0) as usize)
+    }
+    /// Clamps the length range of every selection to the lengths available in its table,
+    /// so a range outside the data still yields a word
+    pub fn with_auto_clamp(&mut self, auto_clamp: bool) -> &mut FluentCodes {
+        self.auto_clamp = auto_clamp;
+        self
+    }
+    /// Only accepts words for which `filter` returns true, re-sampling rejected words
+    pub fn with_word_filter(
+        &mut self,
+        filter: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> &mut FluentCodes {
+        self.word_filter = Some(Arc::new(filter));
+        self
+    }
+    /// Re-samples words that contain the joiner, so the code can be split back into its words
+    pub fn with_joiner_safe(&mut self, joiner_safe: bool) -> &mut FluentCodes {
+        self.joiner_safe = joiner_safe;
+        self
+    }
+    /// Joins the components of the code with random single character symbols of the symbol
+    /// table, or with the joiner when the table has none, wherever it is set in the chain
+    pub fn with_symbol_joiners(&mut self, symbol_joiners: bool) -> &mut FluentCodes {
+        self.symbol_joiners = symbol_joiners;
+        self
+    }
+    /// Only accepts words in which at least `ratio` of the letters are vowels, re-sampling
+    /// other words
+    pub fn with_min_vowel_ratio(&mut self, ratio: f64) -> &mut FluentCodes {
+        self.min_vowel_ratio = Some(ratio);
+        self
+    }
+    /// Makes [`FluentCodes::build`] regenerate the code, at most
+    /// [`FluentCodes::with_max_retries`] times, until its words start with at least `count`
+    /// different letters
+    pub fn with_min_distinct_initials(&mut self, count: usize) -> &mut FluentCodes {
+        self.min_distinct_initials = count;
+        self
+    }
+    /// Sets groups of words that sound alike, such as `to`, `too` and `two`; a word is
+    /// re-sampled when another word of its group was already selected
+    pub fn with_homophone_set(&mut self, groups: Vec<Vec<String>>) -> &mut FluentCodes {
+        self.homophones = groups;
+        self
+    }
+    /// Joins the components of the code with joiners drawn from `joiners` in proportion to
+    /// their weight, wherever it is set in the chain, taking precedence over
+    /// [`FluentCodes::with_symbol_joiners`]
+    pub fn with_weighted_joiners(&mut self, joiners: Vec<(String, u32)>) -> &mut FluentCodes {
+        self.weighted_joiners = joiners;
+        self
+    }
+    /// Sets the fewest in-range words of a word type [`FluentCodes::check_feasibility`]
+    /// accepts, defaults to [`DEFAULT_MIN_POOL_SIZE`]
+    pub fn with_min_pool_size(&mut self, min_pool_size: usize) -> &mut FluentCodes {
+        self.min_pool_size = min_pool_size;
+        self
+    }
+    /// Sets how often a constrained selection re-samples before failing with
+    /// [`FluentCodesError::RetryLimitExceeded`], defaults to [`DEFAULT_MAX_RETRIES`]
+    pub fn with_max_retries(&mut self, max_retries: usize) -> &mut FluentCodes {
+        self.max_retries = max_retries;
+        self
+    }
+    /// Makes [`FluentCodes::random_structure`] always include at least one noun
+    pub fn with_require_noun(&mut self, require_noun: bool) -> &mut FluentCodes {
+        self.require_noun = require_noun;
+        self
+    }
+    /// Makes [`FluentCodes::random_structure`] add words until it holds at least `min_words`
+    /// real words, not counting punctuation and symbols
+    pub fn with_min_words(&mut self, min_words: usize) -> &mut FluentCodes {
+        self.min_words = min_words;
+        self
+    }
+    /// Sets the relative weight of each word type drawn by [`FluentCodes::any_word`] and
+    /// [`FluentCodes::random_structure`]; unlisted types weigh 1 and a weight of 0 excludes
+    /// a type
+    pub fn with_pos_weights(&mut self, weights: HashMap<WordType, u32>) -> &mut FluentCodes {
+        self.pos_weights = weights;
+        self
+    }
+    /// Sets whether [`FluentCodes::any_word`] and [`FluentCodes::random_structure`] draw
+    /// word types uniformly or in proportion to their in-range word counts, before applying
+    /// [`FluentCodes::with_pos_weights`]
+    pub fn with_any_word_mode(&mut self, mode: AnyWordMode) -> &mut FluentCodes {
+        self.any_word_mode = mode;
+        self
+    }
+    /// Sets whether words are selected at random or as the shortest or longest word in
+    /// range, see [`SelectionMode`]
+    pub fn with_selection(&mut self, mode: SelectionMode) -> &mut FluentCodes {
+        self.selection_mode = mode;
+        self
+    }
+    /// Makes [`FluentCodes::natural_phrase`] start with a determiner
+    pub fn with_phrase_determiner(&mut self, determiner: bool) -> &mut FluentCodes {
+        self.phrase_determiner = determiner;
+        self
+    }
+    /// Sets the letter case words are displayed in
+    pub fn with_case(&mut self, case: Case) -> &mut FluentCodes {
+        self.case = Some(case);
+        self
+    }
+    /// Sets the letter case used instead of [`FluentCodes::with_case`] while the joiner is
+    /// empty, e.g. [`Case::Title`] keeps the word boundaries of `FluffyVacuum` visible
+    pub fn with_boundary_case(&mut self, case: Case) -> &mut FluentCodes {
+        self.boundary_case = Some(case);
+        self
+    }
+    /// Sets the letter case of the whole displayed code, digits, joiners and literals
+    /// included, applied after [`FluentCodes::with_case`]
+    pub fn with_output_case(&mut self, case: OutputCase) -> &mut FluentCodes {
+        self.output_case = case;
+        self
+    }
+    /// Capitalizes only the first word, see [`Case::Sentence`]
+    pub fn sentence_case(&mut self) -> &mut FluentCodes {
+        self.with_case(Case::Sentence)
+    }
+    /// Transliterates selected words to ASCII when displayed, e.g. `café` to `cafe`
+    pub fn with_ascii_fold(&mut self, ascii_fold: bool) -> &mut FluentCodes {
+        self.ascii_fold = ascii_fold;
+        self
+    }
+    /// Normalizes words and literals to `form` when displayed, so codes from differently
+    /// composed sources compare equal
+    #[cfg(feature = "unicode-normalization")]
+    pub fn with_unicode_normalization(&mut self, form: Form) -> &mut FluentCodes {
+        self.unicode_normalization = Some(form);
+        self
+    }
+    /// Splits digit blocks into groups of `group_size` digits, counted from the right, e.g.
+    /// `123-456` or `1-234-567` with `-` as `sep`
+    pub fn with_digit_grouping(&mut self, group_size: usize, sep: char) -> &mut FluentCodes {
+        self.digit_grouping = Some((group_size, sep));
+        self
+    }
+    /// Displays the letters of hexadecimal blocks in upper case, e.g. `3FA9`, whatever the
+    /// case of the words
+    pub fn with_hex_uppercase(&mut self, hex_uppercase: bool) -> &mut FluentCodes {
+        self.hex_uppercase = hex_uppercase;
+        self
+    }
+    /// Permutes the characters of each digit block with the generator's random number
+    /// generator, so seeded codes stay reproducible
+    pub fn with_shuffled_digits(&mut self, shuffled_digits: bool) -> &mut FluentCodes {
+        self.shuffled_digits = shuffled_digits;
+        self
+    }
+    /// Keeps digit blocks and numbers from starting with `0`, which systems trimming
+    /// leading zeros would corrupt, by drawing their first digit from 1 to 9
+    pub fn with_no_leading_zero(&mut self, no_leading_zero: bool) -> &mut FluentCodes {
+        self.no_leading_zero = no_leading_zero;
+        self
+    }
+    /// Ends the code with a period after the last word
+    pub fn with_terminal_punctuation(&mut self, terminal_punctuation: bool) -> &mut FluentCodes {
+        self.terminal_punctuation = terminal_punctuation;
+        self
+    }
+    /// Makes [`FluentCodes::build`] fail with [`FluentCodesError::EmptyCode`] when no
+    /// component was chained, instead of returning an empty code
+    pub fn with_require_nonempty(&mut self, require_nonempty: bool) -> &mut FluentCodes {
+        self.require_nonempty = require_nonempty;
+        self
+    }
+    /// Upper cases each letter of the words of the code with a chance of one half, wherever it
+    /// is set in the chain, drawn from the generator's random number generator so seeded
+    /// codes stay reproducible
+    pub fn with_random_caps(&mut self, random_caps: bool) -> &mut FluentCodes {
+        self.random_caps = random_caps;
+        self
+    }
+    /// Follows each word with its part-of-speech tag in parentheses, e.g.
+    /// `fluffy(ADJ)-run(VERB)`, for demonstrations; literals, digits and numbers are not
+    /// labelled. Labelled codes are not recognised by [`FluentCodes::matches_structure`].
+    pub fn with_pos_labels(&mut self, pos_labels: bool) -> &mut FluentCodes {
+        self.pos_labels = pos_labels;
+        self
+    }
+    /// Substitutes letters of words after case styling, `a` by `4`, `e` by `3`, `o` by `0`
+    /// and `s` by `5` unless [`FluentCodes::with_leet_map`] is set
+    pub fn with_leet(&mut self, leet: bool) -> &mut FluentCodes {
+        self.leet = leet;
+        self
+    }
+    /// Sets the letter substitutions of [`FluentCodes::with_leet`], upper case letters are
+    /// substituted like their lower case forms
+    pub fn with_leet_map(&mut self, leet_map: HashMap<char, char>) -> &mut FluentCodes {
+        self.leet_map = leet_map;
+        self
+    }
+    /// Seeds the random number generator used for words and digits, so the same seed and
+    /// chain always produce the same code for a given database
+    pub fn with_seed(&mut self, seed: u64) -> &mut FluentCodes {
+        lock(&self.selection).rng = CodeRng::seed_from_u64(seed);
+        self
+    }
+    /// Sets the database file words are selected from
+    pub fn with_db_path(&mut self, path: impl AsRef<Path>) -> &mut FluentCodes {
+        self.with_provider(SqliteProvider::new(path))
+    }
+    /// Sets the source words are selected from, see [`WordProvider`]
+    pub fn with_provider(&mut self, provider: impl WordProvider + 'static) -> &mut FluentCodes {
+        self.provider = Arc::new(Mutex::new(Box::new(provider)));
+        lock(&self.counts).clear();
+        self
+    }
+    /// Only selects words matching the SQL `predicate`, e.g. `word NOT LIKE '%x%'`, see
+    /// [`SqliteProvider::with_sql_predicate`]. Fails with [`FluentCodesError::Unsupported`]
+    /// when the word source is not a database.
+    ///
+    /// The predicate is inserted into the SQL verbatim and must be trusted input; never
+    /// build it from user input, which allows SQL injection.
+    pub fn with_sql_predicate(&mut self, predicate: impl Into<String>) -> &mut FluentCodes {
+        let set = lock(&self.provider).set_sql_predicate(Some(predicate.into()));
+        match set {
+            Ok(()) => lock(&self.counts).clear(),
+            Err(err) => self.fail(err),
+        }
+        self
+    }
+    /// Only selects words whose lowercase form matches the SQL `GLOB` `pattern`, e.g.
+    /// `b*` or `*[aeiou]`, see [`SqliteProvider::with_sql_like`]. Fails with
+    /// [`FluentCodesError::Unsupported`] when the word source is not a database.
+    ///
+    /// The database skips non-matching words, so unlike [`FluentCodes::with_word_filter`],
+    /// which runs any Rust check on fetched words and re-samples rejected ones, it stays fast
+    /// when most words are rejected, but only supports `GLOB` wildcards: `*`, `?` and
+    /// `[...]` character classes rather than full regular expressions.
+    pub fn with_sql_like(&mut self, pattern: impl Into<String>) -> &mut FluentCodes {
+        let set = lock(&self.provider).set_sql_like(Some(pattern.into()));
+        match set {
+            Ok(()) => lock(&self.counts).clear(),
+            Err(err) => self.fail(err),
+        }
+        self
+    }
+    /// Reserves room for `components` more components, avoiding reallocations while chaining
+    pub fn with_capacity_hint(&mut self, components: usize) -> &mut FluentCodes {
+        self.plan.reserve(components);
+        lock(&self.selection).words.reserve(components);
+        self
+    }
+    /// Removes all components chained so far and any pending error, keeping the configuration
+    pub fn clear(&mut self) -> &mut FluentCodes {
+        self.plan.clear();
+        self.joiner_switches.clear();
+        self.misconfiguration = self.misconfiguration.filter(|it| !it.is_chained());
+        lock(&self.selection).reset();
+        self
+    }
+    /// Returns the number of components chained so far, without selecting them
+    pub fn len(&self) -> usize {
+        self.plan.len()
+    }
+    /// Whether no component was chained yet
+    pub fn is_empty(&self) -> bool {
+        self.plan.is_empty()
+    }
+    /// Returns the most recently chained component, selecting pending components, without
+    /// case styling
+    pub fn last_word(&mut self) -> Option<&str> {
+        drop(self.selected());
+        let selection = self
+            .selection
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        selection.words.last().map(|part| part.text.as_str())
+    }
+    /// Removes the most recently chained component from the code and the plan, selecting
+    /// pending components. The words of a component selecting several, like
+    /// [`FluentCodes::random_structure`], are returned joined by the joiner.
+    pub fn pop_word(&mut self) -> Option<String> {
+        let mut selection = self.selected();
+        let parts = selection.spans.pop().unwrap_or(0);
+        let start = selection.words.len().saturating_sub(parts);
+        let removed: Vec<String> = selection
+            .words
+            .drain(start..)
+            .map(|part| part.text)
+            .collect();
+        selection.selected = selection.selected.saturating_sub(1);
+        drop(selection);
+        self.plan.pop();
+        (!removed.is_empty()).then(|| removed.join(&self.joiner))
+    }
+    /// Reverses the order of the generated components and the plan, not their characters.
+    ///
+    /// Components are reordered before joining, so the joiners drawn for each gap and the
+    /// final joiner stay between the same positions. Components are only selected first
+    /// when some of them already were.
+    pub fn reverse(&mut self) -> &mut FluentCodes {
+        let mut selection = match lock(&self.selection).selected {
+            0 => None,
+            _ => Some(self.selected()),
+        };
+        if let Some(selection) = selection.as_mut() {
+            let mut joiners: Vec<Option<String>> = selection
+                .words
+                .iter_mut()
+                .skip(1)
+                .map(|part| part.joiner.take())
+                .collect();
+            joiners.reverse();
+            selection.words.reverse();
+            selection.spans.reverse();
+            for (part, joiner) in selection.words.iter_mut().skip(1).zip(joiners) {
+                part.joiner = joiner;
+            }
+        }
+        drop(selection);
+        self.plan.reverse();
+        self
+    }
+    /// Returns each generated component as selected, before any styling, with the word type
+    /// it was selected from, `None` for literals and digits
+    pub fn chosen(&self) -> Vec<(String, Option<WordType>)> {
+        self.selected()
+            .words
+            .iter()
+            .map(|part| match part.source {
+                Source::Word(word_type) => (part.text.clone(), Some(word_type)),
+                _ => (part.text.clone(), None),
+            })
+            .collect()
+    }
+    /// Returns the structure of the chained calls, without querying the word source
+    pub fn plan(&self) -> Vec<Component> {
+        self.plan.clone()
+    }
+    /// Returns the components of the code as displayed, without the joiner
+    pub fn words(&self) -> Vec<String> {
+        self.styled(&self.selected())
+    }
+    /// Styles selected components for display, in display order
+    fn styled(&self, selection: &Selection) -> Vec<String> {
+        let case = match self.boundary_case {
+            Some(case) if self.joiner.is_empty() => Some(case),
+            _ => self.case,
+        };
+        self.assembled(selection)
+            .into_iter()
+            .enumerate()
+            .map(|(index, part)| {
+                if let Source::Hex = part.source {
+                    return match self.hex_uppercase {
+                        true => part.text.to_uppercase(),
+                        false => part.text.clone(),
+                    };
+                }
+                let text = match (part.source, self.digit_grouping) {
+                    (Source::Word(_), _) if self.ascii_fold => {
+                        fold::ascii_fold(&self.normalized(&part.text))
+                    }
+                    (Source::Digits(..), Some((size, sep))) => group_digits(&part.text, size, sep),
+                    _ => self.normalized(&part.text),
+                };
+                let mut text = match case {
+                    Some(case) => case.apply(index, &text),
+                    None => text,
+                };
+                if self.random_caps && part.caps.len() == text.chars().count() {
+                    text = text
+                        .chars()
+                        .zip(&part.caps)
+                        .map(|(c, upper)| if *upper { c.to_ascii_uppercase() } else { c })
+                        .collect();
+                }
+                match part.source {
+                    Source::Word(_) if self.leet => text
+                        .chars()
+                        .map(|c| {
+                            let lower = c.to_lowercase().next().unwrap_or(c);
+                            self.leet_map.get(&lower).copied().unwrap_or(c)
+                        })
+                        .collect(),
+                    _ => text,
+                }
+            })
+            .collect()
+    }
+    /// Returns the generated components in display order, see [`FluentCodes::with_digits_last`]
+    /// and [`FluentCodes::with_random_layout`]
+    fn assembled<'a>(&self, selection: &'a Selection) -> Vec<&'a Part> {
+        let mut parts: Vec<&Part> = selection.words.iter().collect();
+        if self.random_layout || self.digits_last {
+            let digits_first = self.random_layout && selection.digits_first;
+            parts.sort_by_key(|part| part.source.is_numeric() != digits_first);
+        }
+        parts
+    }
+    /// Applies the normalization form set by [`FluentCodes::with_unicode_normalization`]
+    fn normalized(&self, text: &str) -> String {
+        #[cfg(feature = "unicode-normalization")]
+        if let Some(form) = self.unicode_normalization {
+            return form.apply(text);
+        }
+        text.to_string()
+    }
+    /// Returns the components of the code as displayed, without the joiner.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a selection error is pending, like displaying the code.
+    pub fn into_parts(self) -> Vec<String> {
+        self.styled(&self.checked_selection())
+    }
+    /// Selects the pending components like [`FluentCodes::selected`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generator is misconfigured or a selection error is pending.
+    fn checked_selection(&self) -> MutexGuard<'_, Selection> {
+        if let Some(misconfiguration) = self.misconfiguration {
+            panic!(
+                "code generation failed: {}, use build() to handle it",
+                misconfiguration.error()
+            );
+        }
+        let selection = self.selected();
+        if let Some(err) = &selection.error {
+            panic!("code generation failed: {}, use build() to handle it", err);
+        }
+        selection
+    }
+    /// Records an invalid setting or component, keeping the first one
+    fn misconfigure(&mut self, misconfiguration: Misconfiguration) {
+        self.misconfiguration.get_or_insert(misconfiguration);
+    }
+    /// Drops an empty length range recorded by [`FluentCodes::with_length_range`] once a
+    /// length is set again
+    fn fix_length_range(&mut self) {
+        if let Some(Misconfiguration::EmptyLengthRange(..)) = self.misconfiguration {
+            self.misconfiguration = None;
+        }
+    }
+    /// Appends `component` to the plan, or records why it cannot be selected
+    fn chain(&mut self, component: Component) -> &mut FluentCodes {
+        match component {
+            Component::Digits(0) | Component::Hex(0) => {
+                self.misconfigure(Misconfiguration::ZeroWidth)
+            }
+            Component::Digits(width) if width > MAX_DIGITS => {
+                self.misconfigure(Misconfiguration::WidthTooLarge(width, MAX_DIGITS))
+            }
+            Component::Hex(width) if width > MAX_HEX_DIGITS => {
+                self.misconfigure(Misconfiguration::WidthTooLarge(width, MAX_HEX_DIGITS))
+            }
+            Component::Number(range) if range.is_empty() => self.misconfigure(
+                Misconfiguration::EmptyValueRange(*range.start(), *range.end()),
+            ),
+            component => self.plan.push(component),
+        }
+        self
+    }
+    /// Selects the pending components and returns the code, or the first error raised while
+    /// selecting its words, after which the next call selects the whole plan again
+    pub fn build(&mut self) -> Result<String, FluentCodesError> {
+        if let Some(misconfiguration) = self.misconfiguration {
+            return Err(misconfiguration.error());
+        }
+        if self.require_nonempty && self.plan.is_empty() {
+            return Err(FluentCodesError::EmptyCode);
+        }
+        let mut regenerations = 0;
+        while self.lacks_initials() {
+            if regenerations == self.max_retries {
+                self.fail(FluentCodesError::RegenerationLimitExceeded(regenerations));
+                break;
+            }
+            regenerations += 1;
+            self.regenerate();
+        }
+        let mut selection = lock(&self.selection);
+        match selection.error.take() {
+            Some(err) => {
+                // the failed components were passed over, so the whole plan is selected anew
+                selection.reset();
+                Err(err)
+            }
+            None => {
+                drop(selection);
+                Ok(self.to_string())
+            }
+        }
+    }
+    /// Yields distinct codes of the planned structure, starting with the current one, until
+    /// `cap` codes are yielded, a selection fails, or [`FluentCodes::with_max_retries`]
+    /// regenerations in a row only repeat earlier codes
+    pub fn unique_codes(mut self, cap: usize) -> impl Iterator<Item = String> {
+        let mut seen = HashSet::new();
+        let mut first = true;
+        std::iter::from_fn(move || {
+            if seen.len() == cap {
+                return None;
+            }
+            for _ in 0..=self.max_retries {
+                if !std::mem::take(&mut first) {
+                    self.regenerate();
+                }
+                let code = self.build().ok()?;
+                if seen.insert(code.clone()) {
+                    return Some(code);
+                }
+            }
+            None
+        })
+    }
+    /// Returns the code like [`FluentCodes::build`], failing with
+    /// [`FluentCodesError::ValidationFailed`] unless [`FluentCodes::matches_structure`] and
+    /// [`FluentCodes::is_reachable`] accept it, which catches words that hold a joiner and
+    /// cannot be split back without [`FluentCodes::with_joiner_escape`]
+    pub fn generate_checked(&mut self) -> Result<String, FluentCodesError> {
+        let code = self.build()?;
+        match self.matches_structure(&code) && self.is_reachable(&code)? {
+            true => Ok(code),
+            false => Err(FluentCodesError::ValidationFailed(code)),
+        }
+    }
+    /// Returns the code like [`FluentCodes::build`] together with the bits of entropy of the
+    /// structure that produced it, see [`FluentCodes::entropy_bits`], for logging strength
+    /// alongside issued codes
+    pub fn generate_with_entropy(&mut self) -> Result<(String, f64), FluentCodesError> {
+        let code = self.build()?;
+        Ok((code, self.entropy_bits()?))
+    }
+    /// Returns a code not in `existing`, regenerating until one is found, for topping up a
+    /// pool of issued codes. Fails with [`FluentCodesError::RegenerationLimitExceeded`] when
+    /// all `attempts` codes were taken.
+    pub fn generate_avoiding(
+        &mut self,
+        existing: &HashSet<String>,
+        attempts: usize,
+    ) -> Result<String, FluentCodesError> {
+        for attempt in 0..attempts {
+            if attempt > 0 {
+                self.regenerate();
+            }
+            let code = self.build()?;
+            if !existing.contains(&code) {
+                return Ok(code);
+            }
+        }
+        Err(FluentCodesError::RegenerationLimitExceeded(attempts))
+    }
+    /// Returns the code as a JSON object with its displayed `parts` and the `entropy_bits`
+    /// of its structure, `null` when the word source cannot count its words
+    #[cfg(feature = "serde")]
+    pub fn generate_json(&mut self) -> Result<serde_json::Value, FluentCodesError> {
+        let code = self.build()?;
+        Ok(serde_json::json!({
+            "code": code,
+            "parts": self.words(),
+            "entropy_bits": self.entropy_bits().ok(),
+        }))
+    }
+    /// Returns the current code and `n - 1` regenerations of the same structure, in
+    /// lexicographic order for stable listings
+    pub fn generate_sorted(&mut self, n: usize) -> Result<Vec<String>, FluentCodesError> {
+        let mut codes = Vec::with_capacity(n);
+        for index in 0..n {
+            if index > 0 {
+                self.regenerate();
+            }
+            codes.push(self.build()?);
+        }
+        codes.sort();
+        Ok(codes)
     }
 }
 
 impl FluentCodes {
-    fn connection_check(&mut self) {
-        if self.connection.is_none() {
-            let path = "./db/words_release.db";
-            self.connection = Connection::open(path).ok();
-        }
-    }
-    fn select_word(&mut self, table: &str) {
-        self.connection_check();
-        let sql = format!(
-            "SELECT LOWER(word) FROM {} where length(word) between {} and  {} \
-                ORDER BY RANDOM() LIMIT 1",
-            table, self.min_length, self.max_length
-        );
-        let val: Result<String, _> = self
-            .connection
-            .as_ref()
-            .unwrap()
-            .query_row(&sql, [], |row| row.get(0));
-        self.words.push(val.unwrap());
+    /// Whether words come from the embedded fallback list because the database could not be opened
+    pub fn is_using_fallback(&self) -> bool {
+        lock(&self.provider).is_using_fallback()
+    }
+    /// Where the words come from, `None` until the database is first queried or when the
+    /// provider is not a word database
+    pub fn db_source(&self) -> Option<DbSource> {
+        lock(&self.provider).db_source()
+    }
+    /// Plans a word of the type, selected by the next terminal call
+    fn select_word(&mut self, word_type: WordType) {
+        self.plan.push(Component::Word(word_type));
+    }
+    fn select_word_now(&self, selection: &mut Selection, word_type: WordType) {
+        match self.pick_word(selection, word_type, self.length_range(word_type)) {
+            Ok(text) => self.push(selection, text, Source::Word(word_type)),
+            Err(err) => selection.fail(err),
+        }
+    }
+    /// Selects `count` words of one type with a single query of the word source, re-sampling
+    /// rejected words one at a time
+    fn select_words(&self, selection: &mut Selection, word_type: WordType, count: usize) {
+        let (min, max) = self.length_range(word_type);
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::debug_span!("select_words", table = word_type.table(), min, max, count)
+                .entered();
+        let batch =
+            lock(&self.provider).random_words(word_type, min, max, count, &mut selection.rng);
+        let words = match batch {
+            Ok(words) => words,
+            Err(err) => return selection.fail(err),
+        };
+        for text in words {
+            let picked = match self.accepts(&selection.words, &text) {
+                true => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(length = text.len(), "selected word");
+                    Ok(text)
+                }
+                false => self.pick_word(selection, word_type, (min, max)),
+            };
+            match picked {
+                Ok(text) => self.push(selection, text, Source::Word(word_type)),
+                Err(err) => selection.fail(err),
+            }
+        }
+    }
+    fn push(&self, selection: &mut Selection, text: String, source: Source) {
+        let joiner = self.draw_joiner(selection);
+        let caps = match source {
+            Source::Word(_) if self.random_caps => {
+                text.chars().map(|_| selection.rng.gen_bool(0.5)).collect()
+            }
+            _ => vec![],
+        };
+        selection.words.push(Part {
+            text,
+            source,
+            joiner,
+            caps,
+        });
+    }
+    /// Draws the joiner of the next part from the weighted joiners or the symbol table, or
+    /// `None` to use the joiner
+    fn draw_joiner(&self, selection: &mut Selection) -> Option<String> {
+        if selection.words.is_empty() {
+            return None;
+        }
+        if !self.weighted_joiners.is_empty() {
+            let weights = self.weighted_joiners.iter().map(|(_, weight)| *weight);
+            let index = WeightedIndex::new(weights).ok()?.sample(&mut selection.rng);
+            return Some(self.weighted_joiners[index].0.clone());
+        }
+        if !self.symbol_joiners {
+            return None;
+        }
+        for _ in 0..=self.max_retries {
+            let symbol = lock(&self.provider)
+                .random_word(WordType::Symbol, 1, 1, &mut selection.rng)
+                .ok()?;
+            if symbol
+                .chars()
+                .all(|c| !c.is_alphanumeric() && !c.is_whitespace())
+            {
+                return Some(symbol);
+            }
+        }
+        None
+    }
+    /// Whether a word sounding like `word` but spelled differently was already selected
+    fn has_chosen_homophone(&self, words: &[Part], word: &str) -> bool {
+        let word = word.to_lowercase();
+        self.homophones
+            .iter()
+            .filter(|group| group.iter().any(|member| member.to_lowercase() == word))
+            .flatten()
+            .map(|member| member.to_lowercase())
+            .filter(|member| *member != word)
+            .any(|member| {
+                words.iter().any(|part| {
+                    matches!(part.source, Source::Word(_)) && part.text.to_lowercase() == member
+                })
+            })
+    }
+    /// Whether the code selected without error has fewer different first letters than
+    /// [`FluentCodes::with_min_distinct_initials`] requires
+    fn lacks_initials(&self) -> bool {
+        let selection = self.selected();
+        selection.error.is_none()
+            && distinct_initials(&selection.words) < self.min_distinct_initials
+    }
+    fn fail(&mut self, err: FluentCodesError) {
+        lock(&self.selection).fail(err);
+    }
+    fn accepts(&self, words: &[Part], word: &str) -> bool {
+        if self.has_chosen_homophone(words, word) {
+            return false;
+        }
+        if let Some(min_ratio) = self.min_vowel_ratio {
+            if vowel_ratio(word) < min_ratio {
+                return false;
+            }
+        }
+        if self.joiner_safe {
+            let mut joiners = std::iter::once(&self.joiner).chain(&self.final_joiner);
+            if joiners.any(|joiner| !joiner.is_empty() && word.contains(joiner.as_str())) {
+                return false;
+            }
+        }
+        self.word_filter.as_ref().is_none_or(|filter| filter(word))
+    }
+    fn length_range(&self, word_type: WordType) -> (usize, usize) {
+        let (min, max) = match self.default_length_map.get(&word_type) {
+            Some(lengths) => *lengths,
+            None => (self.min_length, self.max_length),
+        };
+        if self.auto_clamp {
+            let bounds = lock(&self.provider).length_bounds(word_type);
+            if let Ok((shortest, longest)) = bounds {
+                return (min.clamp(shortest, longest), max.clamp(shortest, longest));
+            }
+        }
+        (min, max)
+    }
+    fn pick_word(
+        &self,
+        selection: &mut Selection,
+        word_type: WordType,
+        (min, max): (usize, usize),
+    ) -> Result<String, FluentCodesError> {
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::debug_span!("select_word", table = word_type.table(), min, max).entered();
+        for _ in 0..=self.max_retries {
+            let text = lock(&self.provider).select_word(
+                word_type,
+                min,
+                max,
+                self.selection_mode,
+                &mut selection.rng,
+            )?;
+            if self.accepts(&selection.words, &text) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(length = text.len(), "selected word");
+                return Ok(text);
+            }
+        }
+        Err(FluentCodesError::RetryLimitExceeded(
+            word_type,
+            self.max_retries,
+        ))
+    }
+    /// Returns the in-range word of a table closest to `input` by Levenshtein distance, and
+    /// that distance, for did-you-mean suggestions. `None` when the table has no word in
+    /// range.
+    #[cfg(feature = "strsim")]
+    pub fn closest_word(
+        &self,
+        table: &str,
+        input: &str,
+    ) -> Result<Option<(String, usize)>, FluentCodesError> {
+        let word_type = WordType::from_table(table)
+            .ok_or_else(|| FluentCodesError::UnknownTable(table.to_string()))?;
+        let (min, max) = self.length_range(word_type);
+        let input = input.to_lowercase();
+        let words = lock(&self.provider).words_in_range(word_type, min, max)?;
+        Ok(words
+            .into_iter()
+            .map(|word| {
+                let distance = strsim::levenshtein(&word, &input);
+                (word, distance)
+            })
+            .min_by_key(|(_, distance)| *distance))
+    }
+    /// Returns the shortest and longest word length available in a table, e.g. `"noun"`
+    pub fn length_bounds(&self, table: &str) -> Result<(usize, usize), FluentCodesError> {
+        let word_type = WordType::from_table(table)
+            .ok_or_else(|| FluentCodesError::UnknownTable(table.to_string()))?;
+        lock(&self.provider).length_bounds(word_type)
+    }
+    /// Returns the number of words of each length in a table, e.g. `"noun"`, for tuning
+    /// length ranges
+    pub fn length_histogram(&self, table: &str) -> Result<BTreeMap<usize, u64>, FluentCodesError> {
+        let word_type = WordType::from_table(table)
+            .ok_or_else(|| FluentCodesError::UnknownTable(table.to_string()))?;
+        lock(&self.provider).length_histogram(word_type)
+    }
+    /// Runs a custom query returning a single row with a single text column on the word
+    /// source's connection, so callers need not open a second one. Parameters are bound to
+    /// `?1`, `?2`, and so on.
+    pub fn query_one(
+        &mut self,
+        sql: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<String, FluentCodesError> {
+        lock(&self.provider).query_one(sql, params)
+    }
+    /// Returns the structures a code of the plan can have, one component per part with the
+    /// index of the planned component it comes from: words drawn for each code become
+    /// [`Component::AnyWord`], as many as [`FluentCodes::with_min_words`] may add, and
+    /// [`FluentCodes::for_length`] becomes its nouns and digit block
+    fn layouts(&self) -> Vec<Vec<(usize, Component)>> {
+        let mut layouts = vec![vec![]];
+        for (index, component) in self.plan.iter().enumerate() {
+            let expansions = match component {
+                Component::RandomStructure(count) => (self.min_words.saturating_sub(*count)
+                    ..=self.min_words)
+                    .map(|extra| vec![Component::AnyWord; count + extra])
+                    .collect(),
+                Component::FitLength(target, tolerance) => {
+                    match self.fit_length(*target, *tolerance) {
+                        Ok((lengths, digits)) => {
+                            let mut expansion =
+                                vec![Component::Word(WordType::Noun); lengths.len()];
+                            if digits > 0 {
+                                expansion.push(Component::Digits(digits));
+                            }
+                            vec![expansion]
+                        }
+                        Err(_) => vec![],
+                    }
+                }
+                component => vec![vec![component.clone()]],
+            };
+            layouts = layouts
+                .iter()
+                .flat_map(|layout| {
+                    expansions.iter().map(move |expansion| {
+                        let expansion = expansion.iter().map(|part| (index, part.clone()));
+                        layout.iter().cloned().chain(expansion).collect()
+                    })
+                })
+                .collect();
+        }
+        layouts
+    }
+    /// Whether a code displayed by this generator splits into parts along one of
+    /// [`FluentCodes::layouts`] that `accept` takes, reading the parts in display order and
+    /// the gaps between them as [`FluentCodes::write_code`] writes them
+    fn splits(
+        &self,
+        code: &str,
+        accept: &mut dyn FnMut(&Component, &str) -> Result<bool, FluentCodesError>,
+    ) -> Result<bool, FluentCodesError> {
+        let mut code = code;
+        if self.terminal_punctuation {
+            match code.strip_suffix('.') {
+                Some(rest) => code = rest,
+                None => return Ok(false),
+            }
+        }
+        let escape = self.joiner_escape.filter(|_| !self.joiner.is_empty());
+        let units = unescaped(code, escape);
+        let escaped = escape.map(|_| Gap::Text(self.joiner.clone()));
+        let display_orders: &[Option<bool>] = match (self.random_layout, self.digits_last) {
+            (true, _) => &[Some(false), Some(true)],
+            (false, true) => &[Some(false)],
+            (false, false) => &[None],
+        };
+        for layout in self.layouts() {
+            for digits_first in display_orders {
+                let mut display: Vec<&(usize, Component)> = layout.iter().collect();
+                if let Some(digits_first) = digits_first {
+                    display.sort_by_key(|(_, component)| component.is_numeric() != *digits_first);
+                }
+                let last = display.len().saturating_sub(1);
+                let gaps: Vec<Vec<Gap>> = display
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (_, component))| self.gaps(&layout, component, index, last))
+                    .collect();
+                let components: Vec<&Component> = display.iter().map(|(_, c)| c).collect();
+                if split_parts(&units, &components, &gaps, escaped.as_ref(), (0, 0), accept)? {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+    /// The joiners that can be displayed before `component` at `index` of the display order
+    /// of `layout`, like [`FluentCodes::write_code`] chooses them
+    fn gaps(
+        &self,
+        layout: &[(usize, Component)],
+        component: &Component,
+        index: usize,
+        last: usize,
+    ) -> Vec<Gap> {
+        match (&self.number_separator, &self.final_joiner) {
+            (Some(sep), _) if component.is_digits() => return vec![Gap::Text(sep.clone())],
+            (_, Some(final_joiner)) if index == last => {
+                return vec![Gap::Text(final_joiner.clone())]
+            }
+            _ => {}
+        }
+        let joiner = self
+            .joiner_switches
+            .iter()
+            .rev()
+            .find(|(position, _)| {
+                layout
+                    .iter()
+                    .filter(|(planned, _)| planned < position)
+                    .count()
+                    <= index
+            })
+            .map_or(&self.joiner, |(_, joiner)| joiner);
+        let weighted: Vec<Gap> = self
+            .weighted_joiners
+            .iter()
+            .filter(|(_, weight)| *weight > 0)
+            .map(|(joiner, _)| Gap::Text(joiner.clone()))
+            .collect();
+        match (weighted.is_empty(), self.symbol_joiners) {
+            (false, _) => weighted,
+            (true, true) => vec![Gap::Symbol, Gap::Text(joiner.clone())],
+            (true, false) => vec![Gap::Text(joiner.clone())],
+        }
+    }
+    /// Removes the digit group separators of a displayed digit block
+    fn ungrouped(&self, part: &str) -> String {
+        match self.digit_grouping {
+            Some((_, sep)) => part.replace(sep, ""),
+            None => part.to_string(),
+        }
+    }
+    /// Whether a displayed part has the shape of `component`
+    fn matches_part(&self, component: &Component, part: &str) -> bool {
+        let digits = |part: &str, width: usize| {
+            let part = self.ungrouped(part);
+            part.len() == width && part.bytes().all(|b| b.is_ascii_digit())
+        };
+        match component {
+            Component::Word(_) | Component::AnyWord => !part.is_empty(),
+            Component::Digits(width) | Component::DrawnDigits(width, _) => digits(part, *width),
+            Component::Hex(width) => {
+                part.len() == *width && part.bytes().all(|b| b.is_ascii_hexdigit())
+            }
+            Component::Number(value_range) => digits(part, value_range.end().to_string().len()),
+            Component::Literal(text) => part.to_lowercase() == text.to_lowercase(),
+            Component::Timestamp => {
+                !part.is_empty() && part.bytes().all(|b| b.is_ascii_alphanumeric())
+            }
+            Component::RandomStructure(_) | Component::FitLength(..) => false,
+        }
+    }
+    /// Whether `code` has the planned structure: one part per component, digit blocks of the
+    /// right width and literals in place. The joiners, escapes, number separator and display
+    /// order set on this generator are followed; joiners drawn by
+    /// [`FluentCodes::with_symbol_joiners`] are read as any single symbol.
+    pub fn matches_structure(&self, code: &str) -> bool {
+        self.splits(code, &mut |component, part| {
+            Ok(self.matches_part(component, part))
+        })
+        .unwrap_or(false)
+    }
+    /// Whether this generator could have produced `code`: besides
+    /// [`FluentCodes::matches_structure`], every word must be in its table within the
+    /// configured length range and every number within its range. Word filters are not
+    /// taken into account.
+    pub fn is_reachable(&self, code: &str) -> Result<bool, FluentCodesError> {
+        let in_table = |word_type: WordType, part: &str| {
+            let (min, max) = self.length_range(word_type);
+            Ok::<_, FluentCodesError>(
+                (min..=max).contains(&part.chars().count())
+                    && lock(&self.provider).contains(word_type, &part.to_lowercase())?,
+            )
+        };
+        self.splits(code, &mut |component, part| {
+            if !self.matches_part(component, part) {
+                return Ok(false);
+            }
+            Ok(match component {
+                Component::Word(word_type) => in_table(*word_type, part)?,
+                Component::AnyWord => {
+                    for word_type in WordType::ALL {
+                        if in_table(word_type, part)? {
+                            return Ok(true);
+                        }
+                    }
+                    false
+                }
+                Component::Number(value_range) => self
+                    .ungrouped(part)
+                    .parse()
+                    .is_ok_and(|value: u64| value_range.contains(&value)),
+                _ => true,
+            })
+        })
+    }
+    /// Returns the tables of [`DEFAULT_TABLES`] that hold at least one word
+    pub fn available_tables(&self) -> Result<Vec<&'static str>, FluentCodesError> {
+        let mut provider = lock(&self.provider);
+        let mut tables = vec![];
+        for table in DEFAULT_TABLES {
+            let word_type = WordType::from_table(table)
+                .ok_or_else(|| FluentCodesError::UnknownTable(table.to_string()))?;
+            match provider.length_bounds(word_type) {
+                Ok(_) => tables.push(*table),
+                Err(FluentCodesError::EmptyTable(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(tables)
+    }
+    /// Fails with [`FluentCodesError::PoolTooSmall`] when a planned word type has fewer
+    /// words in its length range than [`FluentCodes::with_min_pool_size`], which makes
+    /// codes predictable
+    pub fn check_feasibility(&self) -> Result<(), FluentCodesError> {
+        for component in &self.plan {
+            if let Component::Word(word_type) = component {
+                let count = self.in_range_count(*word_type)?;
+                if count < self.min_pool_size as u64 {
+                    return Err(FluentCodesError::PoolTooSmall(
+                        *word_type,
+                        count,
+                        self.min_pool_size,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Returns `n` random words of a table, e.g. `"noun"`, within the configured length range
+    pub fn sample_words(&self, table: &str, n: usize) -> Result<Vec<String>, FluentCodesError> {
+        let word_type = WordType::from_table(table)
+            .ok_or_else(|| FluentCodesError::UnknownTable(table.to_string()))?;
+        let (min, max) = self.length_range(word_type);
+        let mut rng = CodeRng::from_entropy();
+        let mut provider = lock(&self.provider);
+        (0..n)
+            .map(|_| provider.random_word(word_type, min, max, &mut rng))
+            .collect()
+    }
+    /// Returns every word type whose table contains `word`, compared in lowercase
+    pub fn lookup(&self, word: &str) -> Result<Vec<WordType>, FluentCodesError> {
+        let word = word.to_lowercase();
+        let mut provider = lock(&self.provider);
+        let mut word_types = vec![];
+        for word_type in WordType::ALL {
+            if provider.contains(word_type, &word)? {
+                word_types.push(word_type);
+            }
+        }
+        Ok(word_types)
+    }
+    /// Returns how many different codes the planned structure can produce, saturating at
+    /// `u128::MAX`. Word filters are not taken into account.
+    pub fn combination_count(&self) -> Result<u128, FluentCodesError> {
+        let mut total: u128 = 1;
+        for component in &self.plan {
+            total = total.saturating_mul(self.options(component)?);
+        }
+        Ok(total)
+    }
+    /// Returns the bits of entropy each word and digit component of the planned structure
+    /// contributes, in chain order. Word filters are not taken into account.
+    pub fn entropy_breakdown(&self) -> Result<Vec<(Component, f64)>, FluentCodesError> {
+        let mut breakdown = vec![];
+        for component in &self.plan {
+            if let Component::Literal(_) = component {
+                continue;
+            }
+            let options = self.options(component)?;
+            if let (Component::Word(word_type), 0) = (component, options) {
+                let (min, max) = self.length_range(*word_type);
+                return Err(FluentCodesError::NoWordsInRange(*word_type, min, max));
+            }
+            breakdown.push((component.clone(), (options as f64).log2()));
+        }
+        Ok(breakdown)
+    }
+    /// Returns the total bits of entropy of the planned structure, see
+    /// [`FluentCodes::entropy_breakdown`]
+    pub fn entropy_bits(&self) -> Result<f64, FluentCodesError> {
+        Ok(self.entropy_breakdown()?.iter().map(|(_, bits)| bits).sum())
+    }
+    /// Scores how hard the displayed code is to type, lower is easier: a point per
+    /// character, two more per run of digits and per symbol, and one and a half per change
+    /// of case between adjacent letters.
+    ///
+    /// # Panics
+    ///
+    /// Panics like displaying the code if its selection failed.
+    pub fn typing_difficulty(&self) -> f64 {
+        typing_difficulty(&self.to_string())
+    }
+    /// Number of values a single component can take, not counting the words
+    /// [`FluentCodes::with_min_words`] may add
+    fn options(&self, component: &Component) -> Result<u128, FluentCodesError> {
+        Ok(match component {
+            Component::Word(word_type) => self.in_range_count(*word_type)? as u128,
+            Component::Digits(width) | Component::DrawnDigits(width, _) => {
+                10u128.saturating_pow(*width as u32) - self.lowest_value(*width) as u128
+            }
+            Component::Hex(width) => 16u128.saturating_pow(*width as u32),
+            Component::Number(value_range) => {
+                let value_range = self.number_range(value_range);
+                (*value_range.end() as u128 + 1).saturating_sub(*value_range.start() as u128)
+            }
+            Component::Literal(_) => 1,
+            Component::Timestamp => 1,
+            Component::AnyWord => {
+                let mut words: u128 = 0;
+                let weights = self.word_type_weights(false);
+                for (word_type, weight) in WordType::ALL.into_iter().zip(weights) {
+                    if weight > 0 {
+                        words = words.saturating_add(self.in_range_count(word_type)? as u128);
+                    }
+                }
+                words
+            }
+            Component::RandomStructure(count) => self
+                .options(&Component::AnyWord)?
+                .saturating_pow(*count as u32),
+            Component::FitLength(target, tolerance) => {
+                let (lengths, digits) = self.fit_length(*target, *tolerance)?;
+                let mut options = match digits {
+                    0 => 1,
+                    _ => self.options(&Component::Digits(digits))?,
+                };
+                for length in lengths {
+                    let nouns = self.count_in(WordType::Noun, length, length)? as u128;
+                    options = options.saturating_mul(nouns);
+                }
+                options
+            }
+        })
+    }
+    /// Selects a noun phrase: `adjectives` adjectives followed by the noun they modify,
+    /// prefixed by a determiner when [`FluentCodes::with_phrase_determiner`] is set
+    pub fn natural_phrase(&mut self, adjectives: usize) -> &mut FluentCodes {
+        if self.phrase_determiner {
+            self.determiner();
+        }
+        for _ in 0..adjectives {
+            self.adjective();
+        }
+        self.noun()
+    }
+    /// Selects a word of a randomly chosen type, drawn for each code from the word types
+    /// with words in the length range
+    pub fn any_word(&mut self) -> &mut FluentCodes {
+        self.plan.push(Component::AnyWord);
+        self
+    }
+    /// Number of words of the type in its length range, cached per range until the length
+    /// range or the word source changes
+    fn in_range_count(&self, word_type: WordType) -> Result<u64, FluentCodesError> {
+        let (min, max) = self.length_range(word_type);
+        self.count_in(word_type, min, max)
+    }
+    /// Number of words of the type with a length from `min` to `max`, cached per range
+    fn count_in(
+        &self,
+        word_type: WordType,
+        min: usize,
+        max: usize,
+    ) -> Result<u64, FluentCodesError> {
+        if let Some(count) = lock(&self.counts).get(&(word_type, min, max)) {
+            return Ok(*count);
+        }
+        let count = lock(&self.provider).count(word_type, min, max)?;
+        lock(&self.counts).insert((word_type, min, max), count);
+        Ok(count)
+    }
+    /// Weight of each word type of [`WordType::ALL`] when drawn according to
+    /// [`FluentCodes::with_any_word_mode`] and [`FluentCodes::with_pos_weights`], 0 for types
+    /// without words in the length range, and for punctuation and symbols when `words_only`
+    /// is set
+    fn word_type_weights(&self, words_only: bool) -> [u64; WordType::ALL.len()] {
+        WordType::ALL.map(|word_type| {
+            if words_only && !word_type.is_word() {
+                return 0;
+            }
+            let weight = *self.pos_weights.get(&word_type).unwrap_or(&1) as u64;
+            match (self.in_range_count(word_type).ok(), self.any_word_mode) {
+                (Some(0), _) => 0,
+                (Some(count), AnyWordMode::ProportionalToSize) => weight.saturating_mul(count),
+                _ => weight,
+            }
+        })
+    }
+    /// Draws a word type from [`FluentCodes::word_type_weights`]
+    fn draw_word_type(
+        &self,
+        selection: &mut Selection,
+        words_only: bool,
+    ) -> Result<WordType, FluentCodesError> {
+        let index = WeightedIndex::new(self.word_type_weights(words_only))
+            .map_err(|_| FluentCodesError::NoWordTypes)?;
+        Ok(WordType::ALL[index.sample(&mut selection.rng)])
+    }
+    /// Draws the word types of [`FluentCodes::random_structure`]
+    fn draw_structure(
+        &self,
+        selection: &mut Selection,
+        count: usize,
+    ) -> Result<Vec<WordType>, FluentCodesError> {
+        let mut word_types = (0..count)
+            .map(|_| self.draw_word_type(selection, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        let words = word_types
+            .iter()
+            .filter(|word_type| word_type.is_word())
+            .count();
+        for _ in words..self.min_words {
+            word_types.push(self.draw_word_type(selection, true)?);
+        }
+        if self.require_noun && count > 0 && !word_types.contains(&WordType::Noun) {
+            let slot = selection.rng.gen_range(0..word_types.len());
+            word_types[slot] = WordType::Noun;
+        }
+        Ok(word_types)
+    }
+    /// Selects `count` words of randomly chosen types, drawn for each code.
+    ///
+    /// With [`FluentCodes::with_require_noun`] a random slot is replaced by a noun when
+    /// none was selected, and with [`FluentCodes::with_min_words`] real words are added
+    /// until there are enough.
+    pub fn random_structure(&mut self, count: usize) -> &mut FluentCodes {
+        self.plan.push(Component::RandomStructure(count));
+        self
+    }
+    /// Creates a generator of codes of `target` characters, give or take `tolerance`, for
+    /// fixed-width labels: nouns of the noun length range, of lengths chosen to approach the
+    /// target, joined by the joiner and followed by a digit block when the words alone fall
+    /// short. Fewer digits are preferred, then more words; other separators and decorations
+    /// are not counted. The lengths are worked out for each code from the word source and
+    /// length range set by then, so the generator can still be configured. Fails with
+    /// [`FluentCodesError::LengthUnreachable`], reported by [`FluentCodes::build`], when no
+    /// such code exists.
+    pub fn for_length(target: usize, tolerance: usize) -> FluentCodes {
+        let mut codes = FluentCodes::default();
+        codes.plan.push(Component::FitLength(target, tolerance));
+        codes
+    }
+    /// Returns the noun lengths and the number of digits of [`FluentCodes::for_length`]
+    fn fit_length(
+        &self,
+        target: usize,
+        tolerance: usize,
+    ) -> Result<(Vec<usize>, usize), FluentCodesError> {
+        let limit = target.saturating_add(tolerance);
+        let lengths = self.fitting_lengths(limit);
+        let (Some(&shortest), Some(&longest)) = (lengths.first(), lengths.last()) else {
+            return Err(FluentCodesError::LengthUnreachable(target, tolerance));
+        };
+        let joiner = self.joiner.chars().count();
+        // (distance to target, digits, more words first), smallest first, and its lengths
+        let mut best: Option<(usize, usize, std::cmp::Reverse<usize>)> = None;
+        let mut fitted = vec![];
+        for words in 1usize.. {
+            let joiners = (words - 1).saturating_mul(joiner);
+            let shortest_code = words.saturating_mul(shortest).saturating_add(joiners);
+            let beaten =
+                best.is_some_and(|(distance, ..)| shortest_code > target.saturating_add(distance));
+            if shortest_code > limit || beaten {
+                break;
+            }
+            for digits in 0..=MAX_DIGITS {
+                let fixed = match digits {
+                    0 => joiners,
+                    _ => joiners.saturating_add(joiner + digits),
+                };
+                let letters = target.saturating_sub(fixed);
+                // no choice of lengths gets closer than the letters clamped to their bounds
+                let closest = letters.clamp(
+                    words.saturating_mul(shortest),
+                    words.saturating_mul(longest),
+                );
+                let bound = (
+                    closest.saturating_add(fixed).abs_diff(target),
+                    digits,
+                    std::cmp::Reverse(words),
+                );
+                if best.is_some_and(|best| bound >= best) {
+                    continue;
+                }
+                let chosen = spread(&lengths, words, letters);
+                let total = chosen.iter().sum::<usize>().saturating_add(fixed);
+                let rank = (total.abs_diff(target), digits, std::cmp::Reverse(words));
+                if best.is_none_or(|best| rank < best) {
+                    best = Some(rank);
+                    fitted = chosen;
+                }
+            }
+        }
+        match best {
+            Some((distance, digits, _)) if distance <= tolerance => Ok((fitted, digits)),
+            _ => Err(FluentCodesError::LengthUnreachable(target, tolerance)),
+        }
+    }
+    /// Noun lengths of the length range, up to `limit` and the longest noun, with nouns to
+    /// select, shortest first
+    fn fitting_lengths(&self, limit: usize) -> Vec<usize> {
+        let (min, mut max) = self.length_range(WordType::Noun);
+        if let Ok((_, longest)) = lock(&self.provider).length_bounds(WordType::Noun) {
+            max = max.min(longest);
+        }
+        (min.max(1)..=max.min(limit))
+            .filter(|length| !matches!(self.count_in(WordType::Noun, *length, *length), Ok(0)))
+            .collect()
     }
     pub fn adjective(&mut self) -> &mut FluentCodes {
-        self.select_word("adj");
-        return self;
+        self.select_word(WordType::Adjective);
+        self
     }
 
     pub fn adposition(&mut self) -> &mut FluentCodes {
-        self.select_word("adp");
-        return self;
+        self.select_word(WordType::Adposition);
+        self
     }
     pub fn adverb(&mut self) -> &mut FluentCodes {
-        self.select_word("adv");
-        return self;
+        self.select_word(WordType::Adverb);
+        self
     }
     pub fn auxiliary(&mut self) -> &mut FluentCodes {
-        self.select_word("aux");
-        return self;
+        self.select_word(WordType::Auxiliary);
+        self
     }
     pub fn coordinating_conjunction(&mut self) -> &mut FluentCodes {
-        self.select_word("cconj");
-        return self;
+        self.select_word(WordType::CoordinatingConjunction);
+        self
     }
     pub fn determiner(&mut self) -> &mut FluentCodes {
-        self.select_word("det");
-        return self;
+        self.select_word(WordType::Determiner);
+        self
     }
     pub fn interjection(&mut self) -> &mut FluentCodes {
-        self.select_word("intj");
-        return self;
+        self.select_word(WordType::Interjection);
+        self
     }
     pub fn noun(&mut self) -> &mut FluentCodes {
-        self.select_word("noun");
-        return self;
+        self.select_word(WordType::Noun);
+        self
     }
     pub fn particle(&mut self) -> &mut FluentCodes {
-        self.select_word("part");
-        return self;
+        self.select_word(WordType::Particle);
+        self
     }
     pub fn pronoun(&mut self) -> &mut FluentCodes {
-        self.select_word("pron");
-        return self;
+        self.select_word(WordType::Pronoun);
+        self
     }
     pub fn proper_noun(&mut self) -> &mut FluentCodes {
-        self.select_word("propn");
-        return self;
+        self.select_word(WordType::ProperNoun);
+        self
     }
     pub fn punctuation(&mut self) -> &mut FluentCodes {
-        self.select_word("punct");
-        return self;
+        self.select_word(WordType::Punctuation);
+        self
     }
     pub fn subordinating_conjunction(&mut self) -> &mut FluentCodes {
-        self.select_word("sconj");
-        return self;
+        self.select_word(WordType::SubordinatingConjunction);
+        self
     }
     pub fn symbol(&mut self) -> &mut FluentCodes {
-        self.select_word("sym");
-        return self;
+        self.select_word(WordType::Symbol);
+        self
     }
     pub fn verb(&mut self) -> &mut FluentCodes {
-        self.select_word("verb");
-        return self;
+        self.select_word(WordType::Verb);
+        self
     }
-    pub fn six_digits(&mut self) -> &mut FluentCodes {
-        let mut rng = rand::thread_rng();
-        self.words.push(format!("{:#06}", rng.gen_range(0..999999)));
-        return self;
+    /// Appends `token` unchanged apart from case styling
+    pub fn literal(&mut self, token: impl Into<String>) -> &mut FluentCodes {
+        self.plan.push(Component::Literal(token.into()));
+        self
     }
-    pub fn generate_code_with_four_words() -> String {
-        FluentCodes::default()
-            .adjective()
-            .verb()
-            .noun()
-            .adjective()
-            .to_string()
+    /// Inserts `token` before the component at `index`, or appends it when `index` is past
+    /// the end
+    pub fn insert_literal(&mut self, index: usize, token: impl Into<String>) -> &mut FluentCodes {
+        let text = token.into();
+        let index = index.min(self.plan.len());
+        let mut selection = lock(&self.selection);
+        if index < selection.selected {
+            let position = selection.offset(index);
+            selection.spans.insert(index, 1);
+            let mut joiner = self.draw_joiner(&mut selection);
+            if position == 0 {
+                if let Some(first) = selection.words.first_mut() {
+                    first.joiner = joiner.take();
+                }
+            }
+            selection.words.insert(
+                position,
+                Part {
+                    text: text.clone(),
+                    source: Source::Literal,
+                    joiner,
+                    caps: vec![],
+                },
+            );
+            selection.selected += 1;
+        }
+        drop(selection);
+        self.plan.insert(index, Component::Literal(text));
+        self
+    }
+    pub fn six_digits(&mut self) -> &mut FluentCodes {
+        self.digits(6)
+    }
+    /// Appends a block of six digits drawn now from `rng` rather than from the generator's
+    /// random number generator, so the block is deterministic under a caller's seeded
+    /// generator and kept when the code is regenerated
+    pub fn six_digits_with(&mut self, rng: &mut impl Rng) -> &mut FluentCodes {
+        let value = rng.gen_range(self.lowest_value(6)..1_000_000);
+        self.plan.push(Component::DrawnDigits(6, value));
+        self
+    }
+    /// Appends a zero-padded block of `width` random digits, with its digits permuted when
+    /// [`FluentCodes::with_shuffled_digits`] is set. A width of 0, or of more than
+    /// [`MAX_DIGITS`], is reported by every [`FluentCodes::build`] as
+    /// [`FluentCodesError::ZeroWidth`] or [`FluentCodesError::WidthTooLarge`] until the
+    /// components are cleared.
+    pub fn digits(&mut self, width: usize) -> &mut FluentCodes {
+        self.chain(Component::Digits(width))
+    }
+    /// Appends one digit block per entry of `widths`, e.g. `12-3456-78` for `[2, 4, 2]`.
+    /// Invalid widths are reported like [`FluentCodes::digits`] reports them.
+    pub fn digit_groups(&mut self, widths: &[usize]) -> &mut FluentCodes {
+        for width in widths {
+            self.digits(*width);
+        }
+        self
+    }
+    /// Draws a digit block of `width` digits and its value
+    fn draw_digits(&self, selection: &mut Selection, width: usize) -> (String, u64) {
+        let value = selection
+            .rng
+            .gen_range(self.lowest_value(width)..10u64.pow(width as u32));
+        let mut digits = format!("{:0width$}", value).into_bytes();
+        if self.shuffled_digits {
+            digits.shuffle(&mut selection.rng);
+            if self.no_leading_zero && digits.first() == Some(&b'0') {
+                if let Some(nonzero) = digits.iter().position(|digit| *digit != b'0') {
+                    digits.swap(0, nonzero);
+                }
+            }
+        }
+        let digits = String::from_utf8(digits).unwrap();
+        let value = digits.parse().unwrap_or(0);
+        (digits, value)
+    }
+    /// Smallest value of a block of `width` digits, the first without a leading zero when
+    /// [`FluentCodes::with_no_leading_zero`] is set
+    fn lowest_value(&self, width: usize) -> u64 {
+        match self.no_leading_zero && width > 0 {
+            true => 10u64.pow(width as u32 - 1),
+            false => 0,
+        }
+    }
+    /// Values a number component draws from, without those padded with a leading zero
+    /// when [`FluentCodes::with_no_leading_zero`] is set, unless the range only holds `0`
+    fn number_range(&self, value_range: &RangeInclusive<u64>) -> RangeInclusive<u64> {
+        let width = value_range.end().to_string().len();
+        let start = (*value_range.start())
+            .max(self.lowest_value(width))
+            .min(*value_range.end());
+        start..=*value_range.end()
+    }
+    /// Appends a block of `width` random hexadecimal digits, lower case unless
+    /// [`FluentCodes::with_hex_uppercase`] is set. A width of 0, or of more than
+    /// [`MAX_HEX_DIGITS`], is reported by every [`FluentCodes::build`] as
+    /// [`FluentCodesError::ZeroWidth`] or [`FluentCodesError::WidthTooLarge`] until the
+    /// components are cleared.
+    pub fn hex_digits(&mut self, width: usize) -> &mut FluentCodes {
+        self.chain(Component::Hex(width))
+    }
+    /// Appends a random number of `value_range`, zero-padded to the width of its upper bound.
+    /// An empty range is reported by every [`FluentCodes::build`] as
+    /// [`FluentCodesError::EmptyValueRange`] until the components are cleared.
+    pub fn number(&mut self, value_range: RangeInclusive<u64>) -> &mut FluentCodes {
+        self.chain(Component::Number(value_range))
+    }
+    /// Appends the time of selection as base36 seconds since the Unix epoch, e.g. `s44we8`.
+    /// Times before the epoch are written as `0`.
+    #[cfg(feature = "time")]
+    pub fn with_timestamp_base36(&mut self) -> &mut FluentCodes {
+        self.plan.push(Component::Timestamp);
+        self
+    }
+    /// Reads the clock as base36 seconds since the Unix epoch
+    fn timestamp_base36(&self) -> String {
+        const ALPHABET: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+        let mut seconds = (self.clock)()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let mut text = vec![];
+        loop {
+            text.push(ALPHABET[(seconds % 36) as usize]);
+            seconds /= 36;
+            if seconds == 0 {
+                break;
+            }
+        }
+        text.reverse();
+        String::from_utf8(text).unwrap()
+    }
+    /// Sets the clock read by [`FluentCodes::with_timestamp_base36`], the system clock by default
+    #[cfg(feature = "time")]
+    pub fn with_clock(
+        &mut self,
+        clock: impl Fn() -> std::time::SystemTime + Send + Sync + 'static,
+    ) -> &mut FluentCodes {
+        self.clock = Arc::new(clock);
+        self
+    }
+    pub fn generate_code_with_four_words() -> String {
+        FluentCodes::default()
+            .with_capacity_hint(4)
+            .adjective()
+            .verb()
+            .noun()
+            .adjective()
+            .to_string()
+    }
+    pub fn generate_code_with_two_words() -> String {
+        FluentCodes::default()
+            .with_capacity_hint(2)
+            .adjective()
+            .noun()
+            .to_string()
+    }
+    pub fn generate_code_with_two_adjectives_and_noun() -> String {
+        FluentCodes::default()
+            .with_capacity_hint(3)
+            .adjective()
+            .adjective()
+            .noun()
+            .to_string()
     }
     pub fn generate_code_with_three_words_and_six_digits() -> String {
         FluentCodes::default()
+            .with_capacity_hint(4)
             .adjective()
             .verb()
             .noun()
             .six_digits()
             .to_string()
     }
+    /// Regenerates the code a generator configured by `config` and seeded with
+    /// [`FluentCodes::with_seed`] produces, for the same database
+    pub fn reproduce(config: &FluentCodesConfig, seed: u64) -> Result<String, FluentCodesError> {
+        let seeded = FluentCodesConfig {
+            seed: Some(seed),
+            ..config.clone()
+        };
+        FluentCodes::from_config(&seeded).build()
+    }
+    /// Times the generation of `n` codes by one generator created from `config`, reusing
+    /// its word source connection. Codes failing to generate are counted as well, and
+    /// reported in [`BenchStats::failed`].
+    pub fn benchmark(config: &FluentCodesConfig, n: usize) -> BenchStats {
+        let mut codes = FluentCodes::from_config(config);
+        let mut failed = 0;
+        let start = Instant::now();
+        for _ in 0..n {
+            codes.regenerate();
+            if codes.build().is_err() {
+                failed += 1;
+            }
+        }
+        BenchStats::new(n, failed, start.elapsed())
+    }
+    /// Creates a generator for a shorthand pattern: word type letters, see
+    /// [`WordType::from_shorthand`], optionally followed by the width of a digit block.
+    ///
+    /// `"AVN6"` selects an adjective, a verb and a noun followed by six digits.
+    pub fn from_shorthand(pattern: &str) -> Result<FluentCodes, FluentCodesError> {
+        let invalid = || FluentCodesError::InvalidPattern(pattern.to_string());
+        let letters = pattern.trim_end_matches(|c: char| c.is_ascii_digit());
+        let digits = &pattern[letters.len()..];
+        let mut codes = FluentCodes::default();
+        for word_type in WordType::parse_shorthand(letters).map_err(|_| invalid())? {
+            codes.select_word(word_type);
+        }
+        if !digits.is_empty() {
+            match digits.parse::<usize>() {
+                Ok(width @ 1..=MAX_DIGITS) => codes.digits(width),
+                _ => return Err(invalid()),
+            };
+        }
+        Ok(codes)
+    }
+    /// Generates the `index`-th code of the deterministic sequence derived from `seed`.
+    ///
+    /// Codes have the shape of [`FluentCodes::generate_code_with_four_words`], and the same
+    /// `(seed, index)` pair always yields the same code for a given database.
+    pub fn nth(seed: u64, index: u64) -> String {
+        FluentCodes::default()
+            .with_seed(seed ^ mix(index))
+            .with_capacity_hint(4)
+            .adjective()
+            .verb()
+            .noun()
+            .adjective()
+            .to_string()
+    }
+}
+
+/// process-wide generator for FluentCodes struct
+impl FluentCodes {
+    /// Returns the process-wide generator, created on first use.
+    ///
+    /// All callers share one database connection. The generator stays locked while a caller
+    /// holds the guard, so concurrent callers are serialized; components left behind by a
+    /// previous caller are kept until [`FluentCodes::clear`] is called.
+    pub fn global() -> &'static Mutex<FluentCodes> {
+        static GLOBAL: OnceLock<Mutex<FluentCodes>> = OnceLock::new();
+        GLOBAL.get_or_init(|| Mutex::new(FluentCodes::default()))
+    }
+}
+
+/// Generates a four word code with the process-wide generator, see [`FluentCodes::global`]
+pub fn four_words() -> String {
+    let mut codes = FluentCodes::global()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let code = codes
+        .clear()
+        .adjective()
+        .verb()
+        .noun()
+        .adjective()
+        .to_string();
+    codes.clear();
+    code
+}
+
+/// Number of different first letters of the selected words
+fn distinct_initials(words: &[Part]) -> usize {
+    let initials: HashSet<char> = words
+        .iter()
+        .filter(|part| matches!(part.source, Source::Word(_)))
+        .filter_map(|part| part.text.chars().next())
+        .flat_map(char::to_lowercase)
+        .collect();
+    initials.len()
+}
+
+/// Fraction of the letters of `word` that are vowels, `0.0` without letters
+fn vowel_ratio(word: &str) -> f64 {
+    let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+    if letters == 0 {
+        return 0.0;
+    }
+    let vowels = word
+        .chars()
+        .filter(|c| matches!(c.to_ascii_lowercase(), 'a' | 'e' | 'i' | 'o' | 'u'))
+        .count();
+    vowels as f64 / letters as f64
+}
+
+/// Typing difficulty of `code`, see [`FluentCodes::typing_difficulty`]
+fn typing_difficulty(code: &str) -> f64 {
+    let mut score = 0.0;
+    let mut previous: Option<char> = None;
+    for c in code.chars() {
+        score += 1.0;
+        if c.is_ascii_digit() && !previous.is_some_and(|p| p.is_ascii_digit()) {
+            score += 2.0;
+        }
+        if !c.is_alphanumeric() {
+            score += 2.0;
+        }
+        if let Some(p) = previous.filter(|p| p.is_alphabetic() && c.is_alphabetic()) {
+            if p.is_uppercase() != c.is_uppercase() {
+                score += 1.5;
+            }
+        }
+        previous = Some(c);
+    }
+    score
+}
+
+/// Picks `words` of `lengths`, sorted, adding up to `letters` as closely as a greedy choice
+/// gets: each word takes the length nearest to an even share of the letters left that still
+/// lets the remaining words make up the rest
+fn spread(lengths: &[usize], words: usize, letters: usize) -> Vec<usize> {
+    let (shortest, longest) = (lengths[0], lengths[lengths.len() - 1]);
+    let mut left = letters.clamp(
+        words.saturating_mul(shortest),
+        words.saturating_mul(longest),
+    );
+    (1..=words)
+        .rev()
+        .map(|slots| {
+            let rest = slots - 1;
+            let share = (left / slots).clamp(
+                left.saturating_sub(rest.saturating_mul(longest)),
+                left.saturating_sub(rest.saturating_mul(shortest)),
+            );
+            let length = *lengths
+                .iter()
+                .min_by_key(|length| length.abs_diff(share))
+                .unwrap();
+            left = left.saturating_sub(length);
+            length
+        })
+        .collect()
+}
+
+/// Inserts `sep` between groups of `size` digits, counted from the right
+fn group_digits(digits: &str, size: usize, sep: char) -> String {
+    if size == 0 {
+        return digits.to_string();
+    }
+    let mut grouped = String::with_capacity(digits.len() * 2);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(size) {
+            grouped.push(sep);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Locks a word source or a selection, which stays usable when another holder panicked
+fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// splitmix64 finalizer, spreads consecutive indices over the whole seed space
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// compact token encoding for FluentCodes struct
+impl FluentCodes {
+    /// Encodes the generated components into a compact base62 token.
+    ///
+    /// Words are stored as ids of the word provider, so [`FluentCodes::from_token`] only
+    /// recovers the code when it runs against the same database. Words without an id, such
+    /// as those of custom providers, are stored verbatim.
+    pub fn to_token(&self) -> String {
+        let selection = self.selected();
+        let mut provider = lock(&self.provider);
+        let chunks: Vec<Chunk> = selection
+            .words
+            .iter()
+            .map(|part| match part.source {
+                Source::Word(word_type) => match provider.word_id(word_type, &part.text) {
+                    Ok(Some(id)) => Chunk::Word(word_type, id),
+                    _ => Chunk::Text(part.text.clone()),
+                },
+                Source::Digits(width, value) => Chunk::Digits(width, value),
+                Source::Hex | Source::Literal | Source::Timestamp => Chunk::Text(part.text.clone()),
+            })
+            .collect();
+        token::encode(&chunks)
+    }
+    /// Splits a code displayed with [`FluentCodes::with_joiner_escape`] at the unescaped
+    /// occurrences of `joiner`, removing the escape characters
+    pub fn split_escaped(code: &str, joiner: &str, escape: char) -> Vec<String> {
+        let units = unescaped(code, Some(escape));
+        let joiner = Gap::Text(joiner.to_string());
+        let mut parts = vec![];
+        let mut part = String::new();
+        let mut pos = 0;
+        while let Some((c, _)) = units.get(pos) {
+            match joiner.end(&units, pos).filter(|end| *end > pos) {
+                Some(end) => {
+                    parts.push(std::mem::take(&mut part));
+                    pos = end;
+                }
+                None => {
+                    part.push(*c);
+                    pos += 1;
+                }
+            }
+        }
+        parts.push(part);
+        parts
+    }
+    /// Reconstructs the readable code, joined with the default joiner, from a token
+    /// created by [`FluentCodes::to_token`] against the same database. Use
+    /// [`FluentCodes::recover`] to display it like the generator that created it.
+    pub fn from_token(token: &str) -> Result<String, FluentCodesError> {
+        FluentCodes::default().recover(token)
+    }
+    /// Reconstructs the code of a token created by [`FluentCodes::to_token`], looking its
+    /// words up in this generator's word source and displaying it with this generator's
+    /// joiners, case and other display settings.
+    ///
+    /// The token only stores the selected components, so what was drawn while displaying
+    /// them is lost: joiners drawn by [`FluentCodes::with_symbol_joiners`] or
+    /// [`FluentCodes::with_weighted_joiners`], letters upper cased by
+    /// [`FluentCodes::with_random_caps`] and the layout drawn by
+    /// [`FluentCodes::with_random_layout`]. Hexadecimal blocks and timestamps are stored as
+    /// text and come back as displayed by the generator that created the token.
+    pub fn recover(&self, token: &str) -> Result<String, FluentCodesError> {
+        let mut selection = Selection::new(CodeRng::seed_from_u64(0));
+        {
+            let mut provider = lock(&self.provider);
+            for chunk in token::decode(token)? {
+                let (text, source) = match chunk {
+                    Chunk::Word(word_type, id) => (
+                        provider
+                            .word_by_id(word_type, id)?
+                            .ok_or_else(|| FluentCodesError::InvalidToken(token.to_string()))?,
+                        Source::Word(word_type),
+                    ),
+                    Chunk::Text(text) => (text, Source::Literal),
+                    Chunk::Digits(width, value) => {
+                        (format!("{:0width$}", value), Source::Digits(width, value))
+                    }
+                };
+                selection.words.push(Part {
+                    text,
+                    source,
+                    joiner: None,
+                    caps: vec![],
+                });
+                selection.spans.push(1);
+            }
+        }
+        let mut code = String::new();
+        self.write_code(&selection, &mut code)
+            .expect("writing to a String cannot fail");
+        Ok(match self.output_case {
+            OutputCase::AsIs => code,
+            case => case.apply(&code),
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::FluentCodes;
+    use std::collections::{HashMap, HashSet};
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use crate::{
+        lock, AnyWordMode, Case, Component, FluentCodes, FluentCodesConfig, FluentCodesError,
+        InMemoryProvider, OutputCase, SelectionMode, Source, WordProvider, WordType,
+        DEFAULT_MAX_LENGTH, DEFAULT_MIN_LENGTH,
+    };
+    use rand::RngCore;
+
+    /// Serves words from memory, counting the queries
+    struct Counting(InMemoryProvider, Arc<AtomicUsize>);
+
+    impl WordProvider for Counting {
+        fn random_word(
+            &mut self,
+            wt: WordType,
+            min: usize,
+            max: usize,
+            rng: &mut dyn RngCore,
+        ) -> Result<String, FluentCodesError> {
+            self.1.fetch_add(1, Ordering::SeqCst);
+            self.0.random_word(wt, min, max, rng)
+        }
+        fn random_words(
+            &mut self,
+            wt: WordType,
+            min: usize,
+            max: usize,
+            k: usize,
+            rng: &mut dyn RngCore,
+        ) -> Result<Vec<String>, FluentCodesError> {
+            self.1.fetch_add(1, Ordering::SeqCst);
+            self.0.random_words(wt, min, max, k, rng)
+        }
+        fn count(&mut self, wt: WordType, min: usize, max: usize) -> Result<u64, FluentCodesError> {
+            self.1.fetch_add(1, Ordering::SeqCst);
+            self.0.count(wt, min, max)
+        }
+    }
 
     #[test]
     fn print_codes() {
@@ -295,4 +2836,2082 @@ mod tests {
             FluentCodes::generate_code_with_three_words_and_six_digits()
         );
     }
+
+    #[test]
+    fn two_words() {
+        let code = FluentCodes::generate_code_with_two_words();
+        assert_eq!(code.split('-').count(), 2);
+        assert_eq!(code.matches('-').count(), 1);
+    }
+
+    #[test]
+    fn two_adjectives_and_noun() {
+        let code = FluentCodes::generate_code_with_two_adjectives_and_noun();
+        assert_eq!(code.split('-').count(), 3);
+    }
+
+    #[test]
+    fn random_structure_requires_noun() {
+        let mut codes = FluentCodes::default();
+        codes.with_require_noun(true);
+        for _ in 0..20 {
+            codes.clear().random_structure(3);
+            assert_eq!(codes.words().len(), 3);
+            assert!(codes
+                .selected()
+                .words
+                .iter()
+                .any(|part| part.source == Source::Word(WordType::Noun)));
+        }
+    }
+
+    #[test]
+    fn planned_for_length() {
+        let mut codes = FluentCodes::for_length(20, 1);
+        codes.with_length_range(4..=8);
+        for _ in 0..20 {
+            codes.regenerate();
+            let length = codes.build().unwrap().chars().count();
+            assert!((19..=21).contains(&length));
+        }
+        assert_eq!(codes.plan(), [Component::FitLength(20, 1)]);
+        assert!(codes
+            .chosen()
+            .iter()
+            .all(|(_, word_type)| *word_type == Some(WordType::Noun)));
+        let mut codes = FluentCodes::for_length(24, 0);
+        codes.with_length_range(6..=6);
+        let (digits, source) = codes.chosen().pop().unwrap();
+        assert_eq!((digits.len(), source), (3, None));
+        assert_eq!(codes.build().unwrap().chars().count(), 24);
+        let mut codes = FluentCodes::for_length(3, 1);
+        codes.with_length_range(6..=6);
+        assert!(matches!(
+            codes.build(),
+            Err(FluentCodesError::LengthUnreachable(3, 1))
+        ));
+        let words = || {
+            InMemoryProvider::default()
+                .with_words(WordType::Noun, vec!["fox".into(), "otter".into()])
+        };
+        let mut codes = FluentCodes::for_length(9, 0);
+        codes.with_provider(words()).with_length_range(1..=8);
+        assert_eq!(codes.build().unwrap(), "fox-otter");
+        let mut codes = FluentCodes::for_length(3, usize::MAX);
+        codes.with_provider(words()).with_length_range(1..=8);
+        assert_eq!(codes.build().unwrap(), "fox");
+    }
+
+    #[test]
+    fn random_structure_min_words() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Noun, vec!["otter".into()])
+            .with_words(WordType::Symbol, vec!["42".into(), "%".into()]);
+        let mut weights: std::collections::HashMap<WordType, u32> = WordType::ALL
+            .iter()
+            .map(|word_type| (*word_type, 0))
+            .collect();
+        weights.insert(WordType::Noun, 1);
+        weights.insert(WordType::Symbol, 9);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(1..=5)
+            .with_pos_weights(weights)
+            .with_min_words(4);
+        let mut symbols = 0;
+        for _ in 0..20 {
+            let chosen = codes.clear().random_structure(3).chosen();
+            let nouns = chosen
+                .iter()
+                .filter(|(_, word_type)| *word_type == Some(WordType::Noun))
+                .count();
+            assert_eq!(nouns, 4);
+            symbols += chosen.len() - nouns;
+        }
+        assert!(symbols > 0);
+    }
+
+    #[test]
+    fn random_types_drawn_per_code() {
+        let code = |seed_first: bool| {
+            let mut codes = FluentCodes::for_length(18, 2);
+            if seed_first {
+                codes.with_seed(12);
+            }
+            codes
+                .with_length_range(4..=7)
+                .any_word()
+                .random_structure(3);
+            if !seed_first {
+                codes.with_seed(12);
+            }
+            codes.build().unwrap()
+        };
+        assert_eq!(code(true), code(false));
+        let mut codes = FluentCodes::default();
+        codes.with_seed(4).random_structure(3).any_word();
+        let structures: HashSet<Vec<Option<WordType>>> = (0..10)
+            .map(|_| {
+                codes.regenerate();
+                codes
+                    .chosen()
+                    .into_iter()
+                    .map(|(_, source)| source)
+                    .collect()
+            })
+            .collect();
+        assert!(structures.len() > 1);
+    }
+
+    #[test]
+    fn weighted_word_types() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Noun, vec!["otter".into()])
+            .with_words(WordType::Verb, vec!["wander".into()]);
+        let mut weights: std::collections::HashMap<WordType, u32> = WordType::ALL
+            .iter()
+            .map(|word_type| (*word_type, 0))
+            .collect();
+        weights.insert(WordType::Noun, 3);
+        weights.insert(WordType::Verb, 1);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_min_length(1)
+            .with_seed(8)
+            .with_pos_weights(weights)
+            .random_structure(400);
+        let nouns = codes.words().iter().filter(|word| *word == "otter").count();
+        assert_eq!(codes.build().unwrap().split('-').count(), 400);
+        assert!((260..=340).contains(&nouns));
+        let none: std::collections::HashMap<WordType, u32> = WordType::ALL
+            .iter()
+            .map(|word_type| (*word_type, 0))
+            .collect();
+        codes.clear().with_pos_weights(none).any_word();
+        assert!(matches!(codes.build(), Err(FluentCodesError::NoWordTypes)));
+    }
+
+    #[test]
+    fn any_word_proportional_to_size() {
+        let words = InMemoryProvider::default()
+            .with_words(
+                WordType::Noun,
+                [
+                    "otter", "heron", "egret", "tapir", "bison", "koala", "hyena", "llama", "moose",
+                ]
+                .map(String::from)
+                .to_vec(),
+            )
+            .with_words(WordType::Verb, vec!["waver".into()]);
+        let verbs = |mode| {
+            let mut codes = FluentCodes::default();
+            codes
+                .with_provider(words.clone())
+                .with_length_range(5..=5)
+                .with_seed(21)
+                .with_any_word_mode(mode);
+            for _ in 0..1000 {
+                codes.any_word();
+            }
+            codes.build().unwrap().matches("waver").count()
+        };
+        assert!((420..=580).contains(&verbs(AnyWordMode::UniformTable)));
+        let proportional = verbs(AnyWordMode::ProportionalToSize);
+        assert!((60..=140).contains(&proportional));
+        assert_eq!(verbs(AnyWordMode::ProportionalToSize), proportional);
+    }
+
+    #[test]
+    fn random_types_skip_empty_tables() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["red".into()])
+            .with_words(WordType::Noun, vec!["otter".into()])
+            .with_words(WordType::Verb, vec!["wander".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(5..=6)
+            .random_structure(30)
+            .any_word();
+        let code = codes.build().unwrap();
+        assert!(code
+            .split('-')
+            .all(|word| word == "otter" || word == "wander"));
+        codes.clear().with_length_range(20..=30).any_word();
+        assert!(matches!(codes.build(), Err(FluentCodesError::NoWordTypes)));
+    }
+
+    #[test]
+    fn natural_phrase_order() {
+        let mut codes = FluentCodes::default();
+        codes.with_phrase_determiner(true).natural_phrase(2);
+        let sources: Vec<Source> = codes
+            .selected()
+            .words
+            .iter()
+            .map(|part| part.source)
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                Source::Word(WordType::Determiner),
+                Source::Word(WordType::Adjective),
+                Source::Word(WordType::Adjective),
+                Source::Word(WordType::Noun),
+            ]
+        );
+    }
+
+    #[test]
+    fn same_type_runs_batched() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Noun, vec!["otter".into(), "heron".into()])
+            .with_words(WordType::Verb, vec!["wander".into()]);
+        let queries = Arc::new(AtomicUsize::new(0));
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(Counting(words, Arc::clone(&queries)))
+            .with_length_range(5..=6)
+            .noun()
+            .noun()
+            .noun()
+            .verb();
+        let code = codes.build().unwrap();
+        assert_eq!(queries.swap(0, Ordering::SeqCst), 2);
+        let parts: Vec<&str> = code.split('-').collect();
+        assert_eq!(parts.len(), 4);
+        assert!(parts[..3]
+            .iter()
+            .all(|word| *word == "otter" || *word == "heron"));
+        assert_eq!(parts[3], "wander");
+        codes.regenerate();
+        codes.build().unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+        let config = FluentCodesConfig {
+            structure: vec![Component::Word(WordType::Noun); 3],
+            ..FluentCodesConfig::default()
+        };
+        let code = FluentCodes::from_config(&config).build().unwrap();
+        assert!(code.split('-').all(|word| word.len() == 6));
+    }
+
+    #[test]
+    fn selected_on_terminal_call() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["tidy".into()])
+            .with_words(WordType::Noun, vec!["otter".into()]);
+        let queries = Arc::new(AtomicUsize::new(0));
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(Counting(words, Arc::clone(&queries)))
+            .adjective()
+            .noun()
+            .six_digits();
+        assert_eq!(codes.len(), 3);
+        assert_eq!(queries.load(Ordering::SeqCst), 0);
+        codes.with_length_range(4..=5);
+        let code = codes.to_string();
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+        assert!(code.starts_with("tidy-otter-"));
+        assert_eq!(codes.to_string(), code);
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+        codes.noun();
+        assert_eq!(codes.build().unwrap(), format!("{}-otter", code));
+        assert_eq!(queries.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn uppercase_hex() {
+        let words = InMemoryProvider::default().with_words(WordType::Noun, vec!["otter".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(5..=5)
+            .with_hex_uppercase(true)
+            .noun()
+            .hex_digits(16)
+            .noun();
+        let code = codes.build().unwrap();
+        let parts: Vec<&str> = code.split('-').collect();
+        assert_eq!((parts[0], parts[2]), ("otter", "otter"));
+        assert_eq!(parts[1].len(), 16);
+        assert!(parts[1]
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('A'..='F').contains(&c)));
+        assert!(codes.matches_structure(&code));
+        codes.with_hex_uppercase(false);
+        assert_eq!(codes.to_string(), code.to_lowercase());
+    }
+
+    #[test]
+    fn sentence_case_with_period() {
+        let canned = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["fluffy".into()])
+            .with_words(WordType::Noun, vec!["vacuum".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(canned)
+            .with_joiner(" ".to_string())
+            .sentence_case()
+            .adjective()
+            .noun()
+            .adjective();
+        assert_eq!(codes.to_string(), "Fluffy vacuum fluffy");
+        codes.with_terminal_punctuation(true);
+        assert_eq!(codes.to_string(), "Fluffy vacuum fluffy.");
+    }
+
+    #[test]
+    fn empty_joiner_boundaries() {
+        let canned = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["fluffy".into()])
+            .with_words(WordType::Noun, vec!["vacuum".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(canned)
+            .with_joiner(String::new())
+            .adjective()
+            .noun();
+        assert_eq!(codes.to_string(), "fluffyvacuum");
+        codes.with_boundary_case(Case::Title);
+        assert_eq!(codes.to_string(), "FluffyVacuum");
+        codes.with_joiner("-".to_string());
+        assert_eq!(codes.to_string(), "fluffy-vacuum");
+    }
+
+    #[test]
+    fn ascii_folded_words() {
+        let accented = InMemoryProvider::default()
+            .with_words(WordType::Noun, vec!["café".into()])
+            .with_words(WordType::Adjective, vec!["naïve".into()])
+            .with_words(WordType::Verb, vec!["cre\u{301}e".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(accented)
+            .with_min_length(1)
+            .with_max_length(10)
+            .with_ascii_fold(true)
+            .adjective()
+            .noun()
+            .verb();
+        assert_eq!(codes.to_string(), "naive-cafe-cree");
+        codes.with_ascii_fold(false);
+        assert_eq!(codes.to_string(), "naïve-café-cre\u{301}e");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn usize_lengths() {
+        let (min, max): (usize, usize) = (3, 5);
+        let mut codes = FluentCodes::default();
+        codes.with_min_length(min).with_max_length(max);
+        assert_eq!((codes.min_length, codes.max_length), (3, 5));
+        codes.with_min_length_i32(-4).with_max_length_i32(7);
+        assert_eq!((codes.min_length, codes.max_length), (0, 7));
+    }
+
+    #[test]
+    fn oxford_final_joiner() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_joiner(", ".to_string())
+            .with_final_joiner(", and ".to_string())
+            .literal("red")
+            .literal("green")
+            .literal("blue");
+        assert_eq!(codes.to_string(), "red, green, and blue");
+        codes.clear().literal("red").literal("blue");
+        assert_eq!(codes.to_string(), "red, and blue");
+        codes.clear().literal("red");
+        assert_eq!(codes.to_string(), "red");
+        codes.with_final_joiner(" and ".to_string()).literal("blue");
+        assert_eq!(codes.to_string(), "red and blue");
+    }
+
+    #[test]
+    fn grouped_digits() {
+        assert_eq!(crate::group_digits("123456", 3, '-'), "123-456");
+        assert_eq!(crate::group_digits("1234567", 3, '-'), "1-234-567");
+        assert_eq!(crate::group_digits("12345", 2, ' '), "1 23 45");
+        assert_eq!(crate::group_digits("12", 3, '-'), "12");
+        let mut codes = FluentCodes::default();
+        codes
+            .with_joiner("_".to_string())
+            .with_digit_grouping(3, '-')
+            .noun()
+            .six_digits();
+        let code = codes.to_string();
+        let digits = code.split('_').nth(1).unwrap();
+        assert_eq!(digits.len(), 7);
+        assert_eq!(&digits[3..4], "-");
+        assert!(codes.matches_structure(&code));
+    }
+
+    #[test]
+    fn symbol_joiners() {
+        let symbols = InMemoryProvider::default()
+            .with_words(WordType::Symbol, vec!["+".into(), "#".into(), "x".into()])
+            .with_words(WordType::Noun, vec!["otter".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(symbols)
+            .with_min_length(1)
+            .with_symbol_joiners(true);
+        for _ in 0..10 {
+            codes.noun();
+        }
+        let code = codes.to_string();
+        let joiners: Vec<&str> = code.split("otter").filter(|gap| !gap.is_empty()).collect();
+        assert_eq!(joiners.len(), 9);
+        assert!(joiners
+            .iter()
+            .all(|joiner| *joiner == "+" || *joiner == "#"));
+        let mut bundled = FluentCodes::default();
+        bundled.with_symbol_joiners(true).adjective().noun();
+        assert_eq!(bundled.to_string().matches('-').count(), 1);
+    }
+
+    #[cfg(feature = "strsim")]
+    #[test]
+    fn closest_word_typo() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Noun, vec!["otter".into(), "badger".into()]);
+        let mut codes = FluentCodes::default();
+        codes.with_provider(words).with_length_range(5..=6);
+        assert_eq!(
+            codes.closest_word("noun", "Bodger").unwrap(),
+            Some(("badger".to_string(), 1))
+        );
+        assert!(codes.closest_word("nouns", "otter").is_err());
+    }
+
+    #[test]
+    fn escaped_round_trip() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_joiner_escape('\\')
+            .literal("well-known")
+            .literal("back\\slash")
+            .literal("plain");
+        let code = codes.to_string();
+        assert_eq!(code, "well\\-known-back\\\\slash-plain");
+        assert_eq!(
+            FluentCodes::split_escaped(&code, "-", '\\'),
+            vec!["well-known", "back\\slash", "plain"]
+        );
+    }
+
+    #[test]
+    fn chosen_sources() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["tidy".into()])
+            .with_words(WordType::Noun, vec!["otter".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(4..=5)
+            .with_case(Case::Upper)
+            .adjective()
+            .literal("at")
+            .noun()
+            .number(7..=7);
+        assert_eq!(
+            codes.chosen(),
+            vec![
+                ("tidy".to_string(), Some(WordType::Adjective)),
+                ("at".to_string(), None),
+                ("otter".to_string(), Some(WordType::Noun)),
+                ("7".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn unique_codes_exhaust() {
+        let two_nouns = || {
+            let words = InMemoryProvider::default()
+                .with_words(WordType::Noun, vec!["otter".into(), "heron".into()]);
+            let mut codes = FluentCodes::default();
+            codes
+                .with_provider(words)
+                .with_length_range(5..=5)
+                .noun()
+                .noun();
+            codes
+        };
+        assert_eq!(two_nouns().unique_codes(3).count(), 3);
+        let all: HashSet<String> = two_nouns().unique_codes(100).collect();
+        assert_eq!(all.len(), 4);
+    }
+
+    #[test]
+    fn joiner_switched_midway() {
+        let mut codes = FluentCodes::default();
+        codes
+            .literal("fluffy")
+            .literal("vacuum")
+            .switch_joiner("+".into())
+            .literal("misuse")
+            .literal("deadly")
+            .switch_joiner(String::new())
+            .number(42..=42);
+        assert_eq!(codes.to_string(), "fluffy-vacuum+misuse+deadly42");
+        codes.clear().literal("a").literal("b");
+        assert_eq!(codes.to_string(), "a-b");
+    }
+
+    #[test]
+    fn digit_groups_of_widths() {
+        let code = FluentCodes::default()
+            .digit_groups(&[2, 4, 2])
+            .build()
+            .unwrap();
+        let groups: Vec<&str> = code.split('-').collect();
+        assert_eq!(
+            groups.iter().map(|g| g.len()).collect::<Vec<_>>(),
+            [2, 4, 2]
+        );
+        assert!(groups
+            .iter()
+            .all(|group| group.chars().all(|c| c.is_ascii_digit())));
+    }
+
+    #[test]
+    fn typing_difficulty_of_styles() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Noun, vec!["otter".into(), "heron".into()]);
+        let mut plain = FluentCodes::default();
+        plain
+            .with_provider(words.clone())
+            .with_length_range(5..=5)
+            .noun()
+            .noun();
+        let mut symbols = FluentCodes::default();
+        symbols
+            .with_provider(words)
+            .with_length_range(5..=5)
+            .with_joiner("#!".to_string())
+            .with_case(Case::Title)
+            .noun()
+            .literal("@x")
+            .digits(3)
+            .noun();
+        assert_eq!(plain.typing_difficulty(), 13.0);
+        assert!(plain.typing_difficulty() < symbols.typing_difficulty());
+        assert_eq!(super::typing_difficulty("aB1-2"), 5.0 + 1.5 + 4.0 + 2.0);
+    }
+
+    #[test]
+    fn labelled_words() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["fluffy".into()])
+            .with_words(WordType::Verb, vec!["run".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(3..=6)
+            .adjective()
+            .verb()
+            .literal("v1")
+            .digits(2);
+        let plain = codes.to_string();
+        codes.with_pos_labels(true);
+        let labelled = codes.to_string();
+        assert!(labelled.starts_with("fluffy(ADJ)-run(VERB)-v1-"));
+        assert_eq!(labelled.matches('(').count(), 2);
+        assert_eq!(labelled.replace("(ADJ)", "").replace("(VERB)", ""), plain);
+    }
+
+    #[test]
+    fn output_cases() {
+        let words = InMemoryProvider::default().with_words(WordType::Noun, vec!["otter".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(5..=5)
+            .with_case(Case::Title)
+            .with_joiner("_x_".to_string())
+            .noun()
+            .literal("Rev2")
+            .digits(2);
+        let digits = codes.words().last().unwrap().clone();
+        let cases = [
+            (OutputCase::AsIs, "Otter_x_Rev2_x_"),
+            (OutputCase::Lower, "otter_x_rev2_x_"),
+            (OutputCase::Upper, "OTTER_X_REV2_X_"),
+        ];
+        for (case, prefix) in cases {
+            codes.with_output_case(case);
+            assert_eq!(codes.to_string(), format!("{}{}", prefix, digits));
+        }
+    }
+
+    #[test]
+    fn digits_moved_last() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_digits_last(true)
+            .number(1..=1)
+            .literal("alpha")
+            .number(22..=22)
+            .literal("beta");
+        assert_eq!(codes.to_string(), "alpha-beta-1-22");
+    }
+
+    #[test]
+    fn hex_moved_last() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_seed(5)
+            .with_digits_last(true)
+            .hex_digits(4)
+            .literal("alpha")
+            .number(7..=7)
+            .literal("beta");
+        let code = codes.to_string();
+        let parts: Vec<&str> = code.split('-').collect();
+        assert_eq!(parts[..2], ["alpha", "beta"]);
+        assert_eq!(parts[2].len(), 4);
+        assert!(parts[2].bytes().all(|b| b.is_ascii_hexdigit()));
+        assert_eq!(parts[3], "7");
+    }
+
+    #[test]
+    fn seeded_random_layout() {
+        let layouts = || {
+            let mut codes = FluentCodes::default();
+            codes
+                .with_seed(17)
+                .with_random_layout(true)
+                .literal("fluffy")
+                .number(123456..=123456)
+                .literal("vacuum");
+            (0..20)
+                .map(|_| {
+                    let code = codes.build().unwrap();
+                    codes.regenerate();
+                    code
+                })
+                .collect::<Vec<String>>()
+        };
+        let codes = layouts();
+        assert!(codes
+            .iter()
+            .all(|code| code == "123456-fluffy-vacuum" || code == "fluffy-vacuum-123456"));
+        assert!(codes.iter().any(|code| code.starts_with("123456")));
+        assert!(codes.iter().any(|code| code.ends_with("123456")));
+        assert_eq!(layouts(), codes);
+    }
+
+    #[test]
+    fn sorted_batch() {
+        let mut codes = FluentCodes::default();
+        codes.with_seed(3).adjective().noun().six_digits();
+        let batch = codes.generate_sorted(20).unwrap();
+        assert_eq!(batch.len(), 20);
+        assert!(batch.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_code() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["tidy".into(), "calm".into()])
+            .with_words(WordType::Noun, vec!["otter".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(4..=5)
+            .adjective()
+            .noun()
+            .digits(2);
+        let json = codes.generate_json().unwrap();
+        let code = json["code"].as_str().unwrap();
+        assert_eq!(code, codes.to_string());
+        let parts: Vec<&str> = json["parts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|part| part.as_str().unwrap())
+            .collect();
+        assert_eq!(parts.join("-"), code);
+        assert!((json["entropy_bits"].as_f64().unwrap() - 200f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sql_predicate() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_length_range(3..=8)
+            .with_sql_predicate("word NOT LIKE '%e%'");
+        for _ in 0..50 {
+            codes.noun();
+        }
+        let code = codes.build().unwrap();
+        assert!(!code.contains('e'));
+        assert_eq!(code.split('-').count(), 50);
+        assert!(codes
+            .sample_words("verb", 20)
+            .unwrap()
+            .iter()
+            .all(|word| !word.contains('e')));
+        let mut memory = FluentCodes::default();
+        memory
+            .with_provider(InMemoryProvider::default())
+            .with_sql_predicate("1")
+            .noun();
+        assert!(matches!(
+            memory.build(),
+            Err(FluentCodesError::Unsupported("sql_predicate"))
+        ));
+    }
+
+    #[test]
+    fn sql_glob() {
+        let mut codes = FluentCodes::default();
+        codes.with_length_range(3..=8).with_sql_like("b*[aeiou]");
+        for _ in 0..20 {
+            codes.noun();
+        }
+        let code = codes.build().unwrap();
+        assert!(code
+            .split('-')
+            .all(|word| word.starts_with('b') && word.ends_with(['a', 'e', 'i', 'o', 'u'])));
+        let all = codes.clear().noun().combination_count().unwrap();
+        codes.with_sql_like("it's");
+        assert!(codes.combination_count().unwrap() < all);
+    }
+
+    #[test]
+    fn empty_code_required() {
+        let mut codes = FluentCodes::default();
+        assert_eq!(codes.build().unwrap(), "");
+        codes.with_require_nonempty(true);
+        assert!(matches!(codes.build(), Err(FluentCodesError::EmptyCode)));
+        assert_eq!(codes.literal("v1").build().unwrap(), "v1");
+    }
+
+    #[test]
+    fn code_with_entropy() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Noun, vec!["otter".into(), "heron".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(5..=5)
+            .noun()
+            .digits(2);
+        let (code, bits) = codes.generate_with_entropy().unwrap();
+        assert_eq!(code, codes.to_string());
+        assert!((bits - 200f64.log2()).abs() < 1e-9);
+        assert_eq!(bits, codes.entropy_bits().unwrap());
+    }
+
+    #[test]
+    fn avoids_existing_codes() {
+        let words = InMemoryProvider::default().with_words(
+            WordType::Noun,
+            vec!["otter".into(), "heron".into(), "egret".into()],
+        );
+        let mut codes = FluentCodes::default();
+        codes.with_provider(words).with_length_range(5..=5).noun();
+        let existing: HashSet<String> = ["otter", "heron"].map(String::from).into();
+        for _ in 0..20 {
+            assert_eq!(codes.generate_avoiding(&existing, 100).unwrap(), "egret");
+            codes.regenerate();
+        }
+        let existing: HashSet<String> = ["otter", "heron", "egret"].map(String::from).into();
+        assert!(matches!(
+            codes.generate_avoiding(&existing, 5),
+            Err(FluentCodesError::RegenerationLimitExceeded(5))
+        ));
+    }
+
+    #[test]
+    fn checked_round_trip() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_joiner("--".to_string())
+            .with_final_joiner("+".to_string())
+            .with_digit_grouping(3, '-')
+            .adjective()
+            .literal("v-1")
+            .noun()
+            .six_digits();
+        let code = codes.generate_checked().unwrap();
+        assert_eq!(code, codes.to_string());
+        assert!(code.contains('+'));
+        codes
+            .clear()
+            .with_joiner("-".to_string())
+            .with_joiner_escape('\\')
+            .literal("well-known")
+            .noun();
+        let code = codes.generate_checked().unwrap();
+        assert!(code.starts_with("well\\-known"));
+        assert!(codes.matches_structure("well\\-known+otter"));
+        assert!(!codes.matches_structure("well-known+otter"));
+
+        let checked = |configure: &dyn Fn(&mut FluentCodes)| {
+            let mut codes = FluentCodes::default();
+            configure(&mut codes);
+            codes.adjective().noun().six_digits().hex_digits(4);
+            for _ in 0..5 {
+                codes.regenerate();
+                let code = codes.generate_checked().unwrap();
+                assert!(codes.is_reachable(&code).unwrap(), "{}", code);
+            }
+        };
+        checked(&|codes| {
+            codes.with_digits_last(true);
+        });
+        checked(&|codes| {
+            codes.with_random_layout(true);
+        });
+        checked(&|codes| {
+            codes.with_number_separator("#".into());
+        });
+        checked(&|codes| {
+            codes.with_joiner_escape('\\').with_final_joiner("-".into());
+        });
+        checked(&|codes| {
+            codes.with_weighted_joiners(vec![("+".into(), 1), ("~".into(), 1)]);
+        });
+        checked(&|codes| {
+            codes.with_symbol_joiners(true);
+        });
+        checked(&|codes| {
+            codes
+                .literal("x")
+                .switch_joiner("_".into())
+                .random_structure(2);
+        });
+        checked(&|codes| {
+            codes
+                .with_digits_last(true)
+                .with_number_separator("#".into())
+                .with_final_joiner(" and ".into())
+                .six_digits()
+                .switch_joiner(".".into());
+        });
+    }
+
+    #[test]
+    fn eager_open() {
+        let mut codes = FluentCodes::new().unwrap();
+        assert!(!codes.noun().build().unwrap().is_empty());
+        assert!(matches!(
+            FluentCodes::open("./db/missing.db"),
+            Err(FluentCodesError::Database(_))
+        ));
+    }
+
+    #[test]
+    fn number_separator() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["fluffy".into()])
+            .with_words(WordType::Noun, vec!["vacuum".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_number_separator("#".into())
+            .adjective()
+            .noun()
+            .number(123456..=123456);
+        assert_eq!(codes.to_string(), "fluffy-vacuum#123456");
+    }
+
+    #[test]
+    fn reversed_order() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_weighted_joiners(vec![("+".into(), 1)])
+            .literal("one")
+            .literal("two")
+            .digits(2)
+            .reverse();
+        let code = codes.to_string();
+        assert!(code.ends_with("+two+one"));
+        assert_eq!(code.len(), 10);
+        assert!(matches!(codes.plan()[0], Component::Digits(2)));
+    }
+
+    #[test]
+    fn insert_literals() {
+        let mut codes = FluentCodes::default();
+        codes
+            .literal("b")
+            .literal("d")
+            .insert_literal(0, "a")
+            .insert_literal(2, "c")
+            .insert_literal(9, "e");
+        assert_eq!(codes.to_string(), "a-b-c-d-e");
+        assert_eq!(codes.plan()[2], Component::Literal("c".into()));
+    }
+
+    #[cfg(feature = "small-rng")]
+    #[test]
+    fn small_rng_generation() {
+        let mut first = FluentCodes::default();
+        first.with_seed(5).adjective().noun().six_digits();
+        let mut second = FluentCodes::default();
+        second.with_seed(5).adjective().noun().six_digits();
+        let code = first.build().unwrap();
+        assert_eq!(code, second.build().unwrap());
+        assert!(first.matches_structure(&code));
+    }
+
+    #[test]
+    fn seeded_random_caps() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Noun, vec!["otters".into(), "badger".into()]);
+        let mut first = FluentCodes::default();
+        first
+            .with_provider(words.clone())
+            .with_seed(11)
+            .with_random_caps(true)
+            .noun()
+            .noun()
+            .six_digits();
+        let mut second = FluentCodes::default();
+        second
+            .with_provider(words)
+            .with_seed(11)
+            .with_random_caps(true)
+            .noun()
+            .noun()
+            .six_digits();
+        let code = first.build().unwrap();
+        assert_eq!(code, second.build().unwrap());
+        assert!(code.chars().any(|c| c.is_ascii_uppercase()));
+        assert!(code.chars().any(|c| c.is_ascii_lowercase()));
+        let words = first.words();
+        assert_eq!(words[0].to_lowercase(), first.selected().words[0].text);
+        assert!(words[2].chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn leet_substitutions() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["sober".into()])
+            .with_words(WordType::Noun, vec!["oasis".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(5..=5)
+            .with_case(Case::Title)
+            .with_leet(true)
+            .adjective()
+            .noun();
+        assert_eq!(codes.build().unwrap(), "50b3r-045i5");
+        codes.with_leet_map([('o', '0')].into_iter().collect());
+        assert_eq!(codes.build().unwrap(), "S0ber-0asis");
+    }
+
+    #[test]
+    fn tiny_pool_infeasible() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["red".into(), "tan".into()])
+            .with_words(WordType::Noun, vec!["otter".into(); 12]);
+        let mut codes = FluentCodes::default();
+        codes.with_provider(words).with_length_range(3..=5).noun();
+        assert!(codes.check_feasibility().is_ok());
+        codes.adjective();
+        assert!(matches!(
+            codes.check_feasibility(),
+            Err(FluentCodesError::PoolTooSmall(WordType::Adjective, 2, 10))
+        ));
+        codes.with_min_pool_size(2);
+        assert!(codes.check_feasibility().is_ok());
+    }
+
+    #[test]
+    fn length_range() {
+        let mut codes = FluentCodes::default();
+        codes.with_length_range(3..=8);
+        assert_eq!((codes.min_length, codes.max_length), (3, 8));
+        codes.noun().verb();
+        assert!(codes
+            .to_string()
+            .split('-')
+            .all(|word| (3..=8).contains(&word.len())));
+    }
+
+    #[test]
+    fn lengths_per_word_type() {
+        let mut codes = FluentCodes::default();
+        codes.with_default_length_map(HashMap::from([
+            (WordType::Noun, (4, 8)),
+            (WordType::Adjective, (3, 4)),
+        ]));
+        for _ in 0..30 {
+            codes.adjective().noun().verb();
+        }
+        let code = codes.build().unwrap();
+        for (index, word) in code.split('-').enumerate() {
+            let range = match index % 3 {
+                0 => 3..=4,
+                1 => 4..=8,
+                _ => DEFAULT_MIN_LENGTH..=DEFAULT_MAX_LENGTH,
+            };
+            assert!(range.contains(&word.chars().count()), "{}", word);
+        }
+        let config = codes.config();
+        assert_eq!(config.default_length_map[&WordType::Noun], (4, 8));
+    }
+
+    #[test]
+    fn any_length() {
+        let mut codes = FluentCodes::default();
+        codes.without_length_filter();
+        for _ in 0..100 {
+            codes.noun();
+        }
+        let code = codes.build().unwrap();
+        assert!(code.split('-').any(|word| word.len() < 6));
+        let all = codes.clear().noun().combination_count().unwrap();
+        codes.with_length_range(6..=6);
+        assert!(all > codes.combination_count().unwrap());
+    }
+
+    #[test]
+    fn custom_query() {
+        let mut codes = FluentCodes::default();
+        let word = codes
+            .query_one(
+                "SELECT LOWER(word) FROM noun WHERE length(word) = ?1 ORDER BY rowid LIMIT 1",
+                &[&5],
+            )
+            .unwrap();
+        assert_eq!(word.chars().count(), 5);
+        assert!(codes.lookup(&word).unwrap().contains(&WordType::Noun));
+        assert!(matches!(
+            codes.query_one("SELECT word FROM noun WHERE 0", &[]),
+            Err(FluentCodesError::Database(_))
+        ));
+        codes.with_provider(InMemoryProvider::default());
+        assert!(matches!(
+            codes.query_one("SELECT 'otter'", &[]),
+            Err(FluentCodesError::Unsupported("query_one"))
+        ));
+    }
+
+    #[test]
+    fn selection_modes() {
+        let mut codes = FluentCodes::default();
+        let (shortest, longest) = codes.length_bounds("noun").unwrap();
+        codes.with_length_range(3..=8);
+        let lengths = |codes: &mut FluentCodes, mode| {
+            let code = codes
+                .clear()
+                .with_selection(mode)
+                .noun()
+                .noun()
+                .build()
+                .unwrap();
+            let words: Vec<String> = code.split('-').map(String::from).collect();
+            (words[0] == words[1], words[0].chars().count())
+        };
+        assert_eq!(
+            lengths(&mut codes, SelectionMode::Shortest),
+            (true, shortest.max(3))
+        );
+        assert_eq!(
+            lengths(&mut codes, SelectionMode::Longest),
+            (true, longest.min(8))
+        );
+        codes.clear().with_selection(SelectionMode::Random);
+        for _ in 0..20 {
+            codes.noun();
+        }
+        let code = codes.build().unwrap();
+        assert!(code
+            .split('-')
+            .any(|word| word != code.split('-').next().unwrap()));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn empty_length_range() {
+        let mut codes = FluentCodes::default();
+        codes.with_length_range(8..=3).noun();
+        assert!(matches!(
+            codes.build(),
+            Err(FluentCodesError::EmptyLengthRange(8, 3))
+        ));
+        assert_eq!((codes.min_length, codes.max_length), (6, 6));
+        codes.regenerate();
+        assert!(codes.build().is_err());
+        codes.clear().noun();
+        assert!(matches!(
+            codes.build(),
+            Err(FluentCodesError::EmptyLengthRange(8, 3))
+        ));
+        assert!(codes.clone_config().noun().build().is_err());
+        codes.with_length_range(3..=8);
+        assert!(codes.build().is_ok());
+    }
+
+    #[test]
+    fn failed_build_selects_again() {
+        let words = InMemoryProvider::default().with_words(WordType::Noun, vec!["otter".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_length_range(5..=5)
+            .noun()
+            .adjective()
+            .noun();
+        for _ in 0..2 {
+            assert!(matches!(
+                codes.build(),
+                Err(FluentCodesError::NoWordsInRange(WordType::Adjective, 5, 5))
+            ));
+        }
+        assert_eq!(codes.clear().noun().build().unwrap(), "otter");
+    }
+
+    #[test]
+    fn zero_width_blocks() {
+        let mut codes = FluentCodes::default();
+        codes.literal("x").hex_digits(0);
+        for _ in 0..2 {
+            assert!(matches!(codes.build(), Err(FluentCodesError::ZeroWidth)));
+        }
+        codes.regenerate();
+        assert!(matches!(codes.build(), Err(FluentCodesError::ZeroWidth)));
+        codes.clear().literal("x").digit_groups(&[2, 0]);
+        assert!(matches!(codes.build(), Err(FluentCodesError::ZeroWidth)));
+        assert_eq!(codes.plan().len(), 2);
+        let config = FluentCodesConfig {
+            structure: vec![Component::Digits(2)],
+            ..FluentCodesConfig::default()
+        };
+        let mut configured = FluentCodes::from_config(&config);
+        configured.hex_digits(0);
+        assert!(matches!(
+            configured.build(),
+            Err(FluentCodesError::ZeroWidth)
+        ));
+        let config = FluentCodesConfig {
+            structure: vec![Component::Digits(2), Component::Hex(0)],
+            ..FluentCodesConfig::default()
+        };
+        let mut configured = FluentCodes::from_config(&config);
+        assert!(matches!(
+            configured.build(),
+            Err(FluentCodesError::ZeroWidth)
+        ));
+        assert_eq!(FluentCodes::benchmark(&config, 3).failed, 3);
+        assert_eq!(codes.clear().literal("x").build().unwrap(), "x");
+    }
+
+    #[test]
+    fn oversized_blocks() {
+        let mut codes = FluentCodes::default();
+        codes.hex_digits(17);
+        for _ in 0..2 {
+            assert!(matches!(
+                codes.build(),
+                Err(FluentCodesError::WidthTooLarge(17, 16))
+            ));
+        }
+        assert_eq!(codes.clear().hex_digits(16).build().unwrap().len(), 16);
+        codes.clear().digit_groups(&[2, 20]);
+        assert!(matches!(
+            codes.build(),
+            Err(FluentCodesError::WidthTooLarge(20, 19))
+        ));
+        assert_eq!(codes.clear().digits(19).build().unwrap().len(), 19);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn empty_value_range() {
+        let mut codes = FluentCodes::default();
+        codes.number(10..=9);
+        for _ in 0..2 {
+            assert!(matches!(
+                codes.build(),
+                Err(FluentCodesError::EmptyValueRange(10, 9))
+            ));
+        }
+        assert_eq!(codes.clear().number(7..=7).build().unwrap(), "7");
+    }
+
+    #[test]
+    fn weighted_joiners() {
+        let mut codes = FluentCodes::default();
+        codes.with_seed(3).with_weighted_joiners(vec![
+            ("-".into(), 9),
+            ("_".into(), 1),
+            ("+".into(), 0),
+        ]);
+        for _ in 0..=1000 {
+            codes.literal("a");
+        }
+        let code = codes.to_string();
+        let underscores = code.matches('_').count();
+        assert_eq!(code.matches('-').count() + underscores, 1000);
+        assert!((60..=140).contains(&underscores));
+        assert!(!code.contains('+'));
+    }
+
+    #[test]
+    fn default_tables() {
+        assert_eq!(crate::DEFAULT_TABLES.len(), 15);
+        let tables: Vec<&str> = WordType::ALL.iter().map(WordType::table).collect();
+        assert_eq!(crate::DEFAULT_TABLES, tables);
+        assert_eq!(
+            FluentCodes::default().available_tables().unwrap(),
+            crate::DEFAULT_TABLES
+        );
+        let mut codes = FluentCodes::default();
+        codes.with_provider(
+            InMemoryProvider::default().with_words(WordType::Verb, vec!["wander".into()]),
+        );
+        assert_eq!(codes.available_tables().unwrap(), ["verb"]);
+    }
+
+    #[test]
+    fn bundled_length_bounds() {
+        let codes = FluentCodes::default();
+        assert_eq!(codes.length_bounds("noun").unwrap(), (1, 43));
+        assert_eq!(codes.length_bounds("sconj").unwrap(), (2, 15));
+        assert!(matches!(
+            codes.length_bounds("num"),
+            Err(FluentCodesError::UnknownTable(_))
+        ));
+    }
+
+    #[test]
+    fn noun_length_histogram() {
+        let mut codes = FluentCodes::default();
+        let histogram = codes.length_histogram("noun").unwrap();
+        assert_eq!(histogram.keys().next(), Some(&1));
+        assert_eq!(histogram.keys().last(), Some(&43));
+        let total: u64 = histogram.values().sum();
+        codes.without_length_filter().noun();
+        assert_eq!(codes.combination_count().unwrap(), total as u128);
+        assert!(codes.length_histogram("nouns").is_err());
+    }
+
+    #[test]
+    fn lookup_noun_and_verb() {
+        let codes = FluentCodes::default();
+        assert_eq!(
+            codes.lookup("Walk").unwrap(),
+            [WordType::Noun, WordType::ProperNoun, WordType::Verb]
+        );
+        assert!(codes.lookup("qwxzv").unwrap().is_empty());
+    }
+
+    #[test]
+    fn sample_words_in_range() {
+        let mut codes = FluentCodes::default();
+        codes.with_min_length(4).with_max_length(7);
+        let words = codes.sample_words("verb", 12).unwrap();
+        assert_eq!(words.len(), 12);
+        assert!(words.iter().all(|word| (4..=7).contains(&word.len())));
+        assert!(codes.sample_words("nouns", 1).is_err());
+    }
+
+    #[test]
+    fn auto_clamp_out_of_range() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_min_length(50)
+            .with_max_length(60)
+            .with_auto_clamp(true)
+            .noun();
+        assert_eq!(codes.to_string().len(), 43);
+    }
+
+    #[test]
+    fn literal_between_words() {
+        let mut codes = FluentCodes::default();
+        codes.adjective().literal("SKU42").noun();
+        let code = codes.to_string();
+        let parts: Vec<&str> = code.split('-').collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[1], "SKU42");
+        assert_eq!(FluentCodes::from_token(&codes.to_token()).unwrap(), code);
+    }
+
+    #[test]
+    fn retry_limit() {
+        let mut codes = FluentCodes::default();
+        codes.with_word_filter(|_| false).with_max_retries(3).noun();
+        assert!(matches!(
+            codes.build(),
+            Err(FluentCodesError::RetryLimitExceeded(WordType::Noun, 3))
+        ));
+        codes
+            .clear()
+            .with_max_retries(crate::DEFAULT_MAX_RETRIES)
+            .with_word_filter(|word| word.starts_with('s'))
+            .noun();
+        assert!(codes.build().unwrap().starts_with('s'));
+    }
+
+    #[test]
+    fn joiner_safe_words() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_joiner("e".to_string())
+            .with_joiner_safe(true)
+            .adjective()
+            .noun()
+            .verb();
+        let code = codes.build().unwrap();
+        assert_eq!(code.split('e').count(), 3);
+        let trees = InMemoryProvider::default().with_words(WordType::Noun, vec!["tree".into()]);
+        codes.clear().with_provider(trees).with_min_length(4).noun();
+        assert!(matches!(
+            codes.build(),
+            Err(FluentCodesError::RetryLimitExceeded(WordType::Noun, _))
+        ));
+    }
+
+    #[test]
+    fn reachable_codes() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_final_joiner("+".to_string())
+            .adjective()
+            .literal("v1")
+            .noun()
+            .number(10..=99);
+        let code = codes.to_string();
+        assert!(codes.matches_structure(&code));
+        assert!(codes.is_reachable(&code).unwrap());
+        assert!(codes.is_reachable(&code.to_uppercase()).unwrap());
+        let parts = codes.words();
+        let unknown = format!("zzqxjv-v1-{}+{}", parts[2], parts[3]);
+        assert!(codes.matches_structure(&unknown));
+        assert!(!codes.is_reachable(&unknown).unwrap());
+        let too_short = format!("{}-v1-{}+{}", parts[0], parts[2], 5);
+        assert!(!codes.matches_structure(&too_short));
+        let out_of_range = format!("{}-v1-{}+{}", parts[0], parts[2], "05");
+        assert!(codes.matches_structure(&out_of_range));
+        assert!(!codes.is_reachable(&out_of_range).unwrap());
+        assert!(!codes
+            .is_reachable(&format!("{}-{}", parts[0], parts[2]))
+            .unwrap());
+    }
+
+    #[test]
+    fn parts_of_chain() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_joiner("-x-".to_string())
+            .adjective()
+            .literal("SKU")
+            .noun()
+            .six_digits();
+        let code = codes.to_string();
+        let parts = codes.into_parts();
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[1], "SKU");
+        assert_eq!(parts[3].len(), 6);
+        assert_eq!(parts.join("-x-"), code);
+    }
+
+    #[test]
+    fn vowel_ratio_threshold() {
+        let words = InMemoryProvider::default().with_words(
+            WordType::Noun,
+            ["strengths", "banana", "rhythm", "audio", "crypt"]
+                .map(String::from)
+                .to_vec(),
+        );
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_min_length(1)
+            .with_max_length(10)
+            .with_min_vowel_ratio(0.4);
+        for _ in 0..20 {
+            codes.noun();
+        }
+        assert!(codes
+            .build()
+            .unwrap()
+            .split('-')
+            .all(|word| word == "banana" || word == "audio"));
+    }
+
+    #[test]
+    fn distinct_initials() {
+        let words = InMemoryProvider::default().with_words(
+            WordType::Noun,
+            ["apple", "avocado", "banana", "cherry"]
+                .map(String::from)
+                .to_vec(),
+        );
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_min_length(1)
+            .with_max_length(10)
+            .with_min_distinct_initials(3);
+        for _ in 0..5 {
+            codes.clear().noun().noun().noun();
+            let code = codes.build().unwrap();
+            let initials: std::collections::HashSet<char> = code
+                .split('-')
+                .filter_map(|word| word.chars().next())
+                .collect();
+            assert_eq!(initials.len(), 3);
+        }
+        codes.clear().noun().noun().noun().noun();
+        codes.with_min_distinct_initials(4).with_max_retries(5);
+        assert!(matches!(
+            codes.build(),
+            Err(FluentCodesError::RegenerationLimitExceeded(5))
+        ));
+    }
+
+    #[test]
+    fn homophones_avoided() {
+        let words = InMemoryProvider::default().with_words(
+            WordType::Noun,
+            ["to", "too", "two", "tea"].map(String::from).to_vec(),
+        );
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_min_length(1)
+            .with_homophone_set(vec![vec!["to".into(), "too".into(), "Two".into()]]);
+        for _ in 0..10 {
+            codes.noun();
+        }
+        let code = codes.build().unwrap();
+        let homophones: std::collections::HashSet<&str> =
+            code.split('-').filter(|word| *word != "tea").collect();
+        assert!(homophones.len() <= 1);
+    }
+
+    #[test]
+    fn capacity_hint() {
+        let mut codes = FluentCodes::default();
+        codes.with_capacity_hint(4);
+        let (plan, words) = (
+            codes.plan.capacity(),
+            lock(&codes.selection).words.capacity(),
+        );
+        assert!(words >= 4);
+        codes.adjective().verb().noun().six_digits();
+        assert_eq!(
+            (
+                codes.plan.capacity(),
+                lock(&codes.selection).words.capacity()
+            ),
+            (plan, words)
+        );
+        let config = FluentCodesConfig {
+            structure: vec![Component::Digits(2); 9],
+            ..FluentCodesConfig::default()
+        };
+        let codes = FluentCodes::from_config(&config);
+        assert!(lock(&codes.selection).words.capacity() >= 9);
+    }
+
+    #[test]
+    fn len_after_chain() {
+        let mut codes = FluentCodes::default();
+        assert!(codes.is_empty());
+        codes.adjective().noun().literal("x").six_digits();
+        assert_eq!(codes.len(), 4);
+        assert!(!codes.is_empty());
+        codes.clear();
+        assert_eq!(codes.len(), 0);
+    }
+
+    #[test]
+    fn pop_last_word() {
+        let mut codes = FluentCodes::default();
+        assert_eq!(codes.last_word(), None);
+        codes.adjective().literal("v2").noun();
+        let noun = codes.last_word().unwrap().to_string();
+        assert_eq!(codes.pop_word(), Some(noun));
+        assert_eq!(codes.last_word(), Some("v2"));
+        assert_eq!(codes.plan().len(), 2);
+        codes.pop_word();
+        assert_eq!(codes.to_string().split('-').count(), 1);
+        codes.pop_word();
+        assert_eq!(codes.pop_word(), None);
+        assert!(codes.plan().is_empty());
+    }
+
+    #[test]
+    fn plan_matches_chain() {
+        let mut codes = FluentCodes::default();
+        codes.adjective().literal("x").noun().six_digits();
+        assert_eq!(
+            codes.plan(),
+            [
+                Component::Word(WordType::Adjective),
+                Component::Literal("x".to_string()),
+                Component::Word(WordType::Noun),
+                Component::Digits(6),
+            ]
+        );
+        codes.clear().random_structure(4);
+        assert_eq!(codes.plan(), [Component::RandomStructure(4)]);
+        assert_eq!(codes.words().len(), 4);
+    }
+
+    #[test]
+    fn combinations_of_small_dictionary() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["red".into(), "blue".into()])
+            .with_words(
+                WordType::Noun,
+                vec!["fox".into(), "owl".into(), "cat".into(), "otter".into()],
+            );
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_min_length(3)
+            .with_max_length(4)
+            .adjective()
+            .noun()
+            .literal("v1")
+            .six_digits();
+        assert_eq!(codes.combination_count().unwrap(), 2 * 3 * 1_000_000);
+        codes.clear();
+        for _ in 0..40 {
+            codes.six_digits();
+        }
+        assert_eq!(codes.combination_count().unwrap(), u128::MAX);
+    }
+
+    #[test]
+    fn entropy_per_component() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["red".into(), "tan".into()])
+            .with_words(
+                WordType::Noun,
+                vec!["fox".into(), "owl".into(), "cat".into(), "emu".into()],
+            );
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_min_length(3)
+            .with_max_length(3)
+            .adjective()
+            .literal("v1")
+            .noun()
+            .six_digits();
+        let breakdown = codes.entropy_breakdown().unwrap();
+        assert_eq!(
+            breakdown.iter().map(|(c, _)| c.clone()).collect::<Vec<_>>(),
+            [
+                Component::Word(WordType::Adjective),
+                Component::Word(WordType::Noun),
+                Component::Digits(6),
+            ]
+        );
+        assert_eq!(breakdown[0].1, 1.0);
+        assert_eq!(breakdown[1].1, 2.0);
+        let sum: f64 = breakdown.iter().map(|(_, bits)| bits).sum();
+        assert!((sum - codes.entropy_bits().unwrap()).abs() < 1e-9);
+        assert!((sum - (8_000_000f64).log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn counts_cached_per_range() {
+        let words = InMemoryProvider::default().with_words(
+            WordType::Noun,
+            vec!["fox".into(), "owl".into(), "otter".into()],
+        );
+        let queries = Arc::new(AtomicUsize::new(0));
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(Counting(words, Arc::clone(&queries)))
+            .with_length_range(3..=3)
+            .with_min_pool_size(2)
+            .noun()
+            .noun();
+        for _ in 0..3 {
+            assert_eq!(codes.entropy_bits().unwrap(), 2.0);
+        }
+        codes.check_feasibility().unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+        codes.with_length_range(3..=5);
+        assert!((codes.entropy_bits().unwrap() - 9f64.log2()).abs() < 1e-9);
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn shorthand_pattern() {
+        let codes = FluentCodes::from_shorthand("AVN6").unwrap();
+        assert_eq!(
+            codes.plan(),
+            [
+                Component::Word(WordType::Adjective),
+                Component::Word(WordType::Verb),
+                Component::Word(WordType::Noun),
+                Component::Digits(6),
+            ]
+        );
+        let code = codes.to_string();
+        assert_eq!(code.rsplit('-').next().unwrap().len(), 6);
+        assert_eq!(FluentCodes::from_shorthand("n").unwrap().plan().len(), 1);
+        for invalid in ["A6N", "AB", "N20", "N0"] {
+            assert!(matches!(
+                FluentCodes::from_shorthand(invalid),
+                Err(FluentCodesError::InvalidPattern(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn generators_from_one_config() {
+        let config = FluentCodesConfig {
+            structure: vec![
+                Component::Word(WordType::Adjective),
+                Component::Literal("x".to_string()),
+                Component::Word(WordType::Noun),
+                Component::Digits(4),
+            ],
+            joiner: "_".to_string(),
+            min_length: 3,
+            max_length: 8,
+            ..FluentCodesConfig::default()
+        };
+        for _ in 0..3 {
+            let mut codes = FluentCodes::from_config(&config);
+            assert_eq!(codes.plan(), config.structure);
+            let code = codes.build().unwrap();
+            let parts: Vec<&str> = code.split('_').collect();
+            assert_eq!(parts.len(), 4);
+            assert_eq!(parts[1], "x");
+            assert!((3..=8).contains(&parts[2].len()));
+        }
+        let seeded = FluentCodesConfig {
+            seed: Some(5),
+            ..config.clone()
+        };
+        assert_eq!(
+            FluentCodes::from_config(&seeded).to_string(),
+            FluentCodes::from_config(&seeded).to_string()
+        );
+    }
+
+    #[test]
+    fn number_of_billions() {
+        let mut codes = FluentCodes::default();
+        codes.number(1_000_000_000..=9_999_999_999).number(0..=42);
+        let code = codes.to_string();
+        let (big, small) = code.split_once('-').unwrap();
+        assert_eq!(big.len(), 10);
+        assert!((1_000_000_000..=9_999_999_999).contains(&big.parse::<u64>().unwrap()));
+        assert_eq!(small.len(), 2);
+        assert_eq!(codes.combination_count().unwrap(), 9_000_000_000 * 43);
+        assert_eq!(FluentCodes::from_token(&codes.to_token()).unwrap(), code);
+        FluentCodes::default().number(0..=u64::MAX);
+    }
+
+    #[test]
+    fn reproduce_seeded_code() {
+        let code = FluentCodes::default()
+            .with_joiner(".".to_string())
+            .with_seed(2024)
+            .adjective()
+            .noun()
+            .six_digits()
+            .verb()
+            .build()
+            .unwrap();
+        let config = FluentCodesConfig {
+            structure: vec![
+                Component::Word(WordType::Adjective),
+                Component::Word(WordType::Noun),
+                Component::Digits(6),
+                Component::Word(WordType::Verb),
+            ],
+            joiner: ".".to_string(),
+            ..FluentCodesConfig::default()
+        };
+        assert_eq!(FluentCodes::reproduce(&config, 2024).unwrap(), code);
+        assert_ne!(FluentCodes::reproduce(&config, 2025).unwrap(), code);
+    }
+
+    #[test]
+    fn benchmark_stats() {
+        let config = FluentCodesConfig {
+            structure: vec![Component::Word(WordType::Noun), Component::Digits(6)],
+            ..FluentCodesConfig::default()
+        };
+        let stats = FluentCodes::benchmark(&config, 20);
+        assert_eq!(stats.codes, 20);
+        assert_eq!(stats.failed, 0);
+        assert!(stats.total > std::time::Duration::ZERO);
+        assert!(stats.per_code > std::time::Duration::ZERO && stats.per_code <= stats.total);
+        assert!(stats.codes_per_sec > 0.0);
+        assert_eq!(
+            FluentCodes::benchmark(&config, 0).per_code,
+            Default::default()
+        );
+    }
+
+    #[test]
+    fn clone_configured_base() {
+        let words = InMemoryProvider::default().with_words(
+            WordType::Noun,
+            ["otter", "badger", "heron", "marten", "beaver", "walrus"]
+                .map(String::from)
+                .to_vec(),
+        );
+        let mut base = FluentCodes::default();
+        base.with_provider(words)
+            .with_joiner("+".to_string())
+            .with_length_range(5..=6)
+            .with_case(Case::Upper)
+            .with_word_filter(|word| word != "heron")
+            .noun();
+        let codes: std::collections::HashSet<String> = (0..8)
+            .map(|_| {
+                let mut codes = base.clone_config();
+                assert!(codes.is_empty());
+                codes.noun().noun().noun().six_digits().build().unwrap()
+            })
+            .collect();
+        assert!(codes.len() > 1);
+        for code in codes {
+            let parts: Vec<&str> = code.split('+').collect();
+            assert_eq!(parts.len(), 4);
+            assert!(parts[..3].iter().all(|word| *word == word.to_uppercase()));
+            assert!(!parts.contains(&"HERON"));
+        }
+    }
+
+    #[test]
+    fn token_round_trip() {
+        let mut codes = FluentCodes::default();
+        codes.adjective().noun().six_digits().verb();
+        let token = codes.to_token();
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_eq!(FluentCodes::from_token(&token).unwrap(), codes.to_string());
+        assert!(FluentCodes::from_token("not a token").is_err());
+    }
+
+    #[test]
+    fn token_recovered_with_display_settings() {
+        let words = InMemoryProvider::default()
+            .with_words(WordType::Adjective, vec!["jagged".into()])
+            .with_words(WordType::Noun, vec!["winter".into()]);
+        let mut codes = FluentCodes::default();
+        codes
+            .with_provider(words)
+            .with_joiner("_".into())
+            .with_output_case(OutputCase::Upper)
+            .adjective()
+            .noun()
+            .digits(2);
+        let token = codes.to_token();
+        let code = codes.to_string();
+        assert!(code.starts_with("JAGGED_WINTER_"));
+        assert_eq!(codes.recover(&token).unwrap(), code);
+        assert_eq!(codes.clone_config().recover(&token).unwrap(), code);
+        assert_eq!(
+            FluentCodes::from_token(&token).unwrap(),
+            code.to_lowercase().replace('_', "-")
+        );
+    }
+
+    #[test]
+    fn long_literal_token() {
+        let long = "x".repeat(5000);
+        let mut codes = FluentCodes::default();
+        codes.literal(long.as_str()).six_digits();
+        let token = codes.to_token();
+        assert_eq!(FluentCodes::from_token(&token).unwrap(), codes.to_string());
+        assert!(FluentCodes::from_token(&token[..token.len() / 2]).is_err());
+        assert!(matches!(
+            FluentCodes::from_token("UBAzL8n0Y58mD"),
+            Err(FluentCodesError::InvalidToken(_))
+        ));
+    }
+
+    #[test]
+    fn nth_is_stable() {
+        assert_eq!(FluentCodes::nth(42, 7), FluentCodes::nth(42, 7));
+        let codes: std::collections::HashSet<String> =
+            (0..10).map(|index| FluentCodes::nth(42, index)).collect();
+        assert!(codes.len() > 8);
+    }
+
+    #[test]
+    fn seeded_words_and_digits() {
+        let code = |seed| {
+            FluentCodes::default()
+                .with_seed(seed)
+                .adjective()
+                .six_digits()
+                .noun()
+                .six_digits()
+                .to_string()
+        };
+        assert_eq!(code(7), code(7));
+        let codes: std::collections::HashSet<String> = (0..10).map(code).collect();
+        assert!(codes.len() > 8);
+    }
+
+    #[test]
+    fn no_leading_zeros() {
+        let mut codes = FluentCodes::default();
+        codes.with_no_leading_zero(true);
+        for shuffled in [false, true] {
+            codes.with_shuffled_digits(shuffled);
+            for _ in 0..200 {
+                codes.clear().digits(1).digits(3).number(0..=999);
+                let code = codes.build().unwrap();
+                assert!(code.split('-').all(|block| !block.starts_with('0')));
+                assert!(code.split('-').map(str::len).eq([1, 3, 3]));
+            }
+        }
+        codes.clear().digits(2);
+        assert!((codes.entropy_bits().unwrap() - 90f64.log2()).abs() < 1e-9);
+        codes.clear().number(0..=99);
+        assert!((codes.entropy_bits().unwrap() - 90f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn digits_rerolled() {
+        let mut codes = FluentCodes::default();
+        codes
+            .noun()
+            .digits(12)
+            .adjective()
+            .hex_digits(8)
+            .number(0..=u64::MAX);
+        let before: Vec<String> = codes.words();
+        codes.reroll_digits();
+        let after = codes.words();
+        assert_eq!((&after[0], &after[2]), (&before[0], &before[2]));
+        for index in [1, 3, 4] {
+            assert_ne!(after[index], before[index]);
+        }
+        assert_eq!(
+            FluentCodes::from_token(&codes.to_token()).unwrap(),
+            codes.to_string()
+        );
+    }
+
+    #[test]
+    fn digits_from_caller_rng() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        let block = || {
+            let mut codes = FluentCodes::default();
+            codes.six_digits_with(&mut StdRng::seed_from_u64(7));
+            codes
+        };
+        let mut codes = block();
+        let digits = codes.to_string();
+        assert_eq!(digits.len(), 6);
+        assert!(digits.bytes().all(|b| b.is_ascii_digit()));
+        assert_eq!(block().to_string(), digits);
+        codes.regenerate();
+        assert_eq!(codes.to_string(), digits);
+        assert!(codes.matches_structure(&digits));
+        assert_eq!(FluentCodes::from_token(&codes.to_token()).unwrap(), digits);
+    }
+
+    #[test]
+    fn seeded_digit_shuffle() {
+        let block = |shuffled| {
+            FluentCodes::default()
+                .with_seed(11)
+                .with_shuffled_digits(shuffled)
+                .digits(12)
+                .to_string()
+        };
+        let (plain, shuffled) = (block(false), block(true));
+        assert_eq!(shuffled, block(true));
+        assert_ne!(shuffled, plain);
+        let sorted = |digits: &str| {
+            let mut digits = digits.as_bytes().to_vec();
+            digits.sort_unstable();
+            digits
+        };
+        assert_eq!(sorted(&shuffled), sorted(&plain));
+        let mut codes = FluentCodes::default();
+        codes.with_shuffled_digits(true).six_digits();
+        assert_eq!(
+            FluentCodes::from_token(&codes.to_token()).unwrap(),
+            codes.to_string()
+        );
+    }
+
+    #[test]
+    fn global_from_threads() {
+        let handles: Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(crate::four_words))
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap().split('-').count(), 4);
+        }
+    }
+
+    #[test]
+    fn fallback_without_database() {
+        let mut codes = FluentCodes::default();
+        codes
+            .with_db_path("./db/missing.db")
+            .adjective()
+            .adposition()
+            .adverb()
+            .auxiliary()
+            .coordinating_conjunction()
+            .determiner()
+            .interjection()
+            .noun()
+            .particle()
+            .pronoun()
+            .proper_noun()
+            .punctuation()
+            .subordinating_conjunction()
+            .symbol()
+            .verb()
+            .six_digits();
+        assert_eq!(codes.to_string().split('-').count(), 16);
+        assert!(codes.is_using_fallback());
+        assert_eq!(
+            FluentCodes::from_token(&codes.to_token()).unwrap(),
+            codes.to_string()
+        );
+        assert!(!FluentCodes::default().adjective().is_using_fallback());
+    }
+
+    #[test]
+    fn custom_provider() {
+        struct Canned;
+        impl WordProvider for Canned {
+            fn random_word(
+                &mut self,
+                wt: WordType,
+                _min: usize,
+                _max: usize,
+                _rng: &mut dyn RngCore,
+            ) -> Result<String, FluentCodesError> {
+                Ok(wt.table().to_string())
+            }
+        }
+        let mut codes = FluentCodes::default();
+        codes.with_provider(Canned).adjective().noun().six_digits();
+        assert!(codes.to_string().starts_with("adj-noun-"));
+        assert_eq!(
+            FluentCodes::from_token(&codes.to_token()).unwrap(),
+            codes.to_string()
+        );
+    }
+
+    #[test]
+    fn configured_timestamp() {
+        let config = FluentCodesConfig {
+            structure: vec![Component::Timestamp, Component::Literal("v1".into())],
+            digits_last: true,
+            ..FluentCodesConfig::default()
+        };
+        let code = FluentCodes::from_config(&config).build().unwrap();
+        let (literal, timestamp) = code.split_once('-').unwrap();
+        assert_eq!(literal, "v1");
+        assert!(timestamp.len() >= 6 && timestamp.bytes().all(|b| b.is_ascii_alphanumeric()));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn fixed_clock_timestamp() {
+        use std::time::{Duration, UNIX_EPOCH};
+        let mut codes = FluentCodes::default();
+        codes
+            .with_clock(|| UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+            .literal("v1")
+            .with_timestamp_base36();
+        assert_eq!(codes.to_string(), "v1-s44we8");
+        assert_eq!(codes.plan()[1], Component::Timestamp);
+        codes.clear().with_digits_last(true);
+        codes.with_timestamp_base36().literal("v1");
+        assert_eq!(codes.to_string(), "v1-s44we8");
+        codes
+            .clear()
+            .with_clock(|| UNIX_EPOCH)
+            .with_timestamp_base36();
+        assert_eq!(codes.to_string(), "0");
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn normalized_compositions() {
+        let code = |cafe: &str, form| {
+            FluentCodes::default()
+                .with_unicode_normalization(form)
+                .literal(cafe)
+                .to_string()
+        };
+        let (composed, decomposed) = ("caf\u{e9}", "cafe\u{301}");
+        assert_ne!(composed, decomposed);
+        assert_eq!(
+            code(composed, crate::Form::Nfc),
+            code(decomposed, crate::Form::Nfc)
+        );
+        assert_eq!(code(composed, crate::Form::Nfc), composed);
+        assert_eq!(code(composed, crate::Form::Nfd), decomposed);
+        assert_eq!(code("\u{fb01}ne", crate::Form::Nfkc), "fine");
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_events() {
+        use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata};
+
+        #[derive(Default)]
+        struct Counter {
+            spans: AtomicU64,
+            events: AtomicUsize,
+        }
+        impl tracing::Subscriber for &'static Counter {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(self.spans.fetch_add(1, Ordering::SeqCst) + 1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &Event<'_>) {
+                self.events.fetch_add(1, Ordering::SeqCst);
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let counter: &'static Counter = Box::leak(Box::default());
+        tracing::subscriber::with_default(counter, || {
+            FluentCodes::default().adjective().noun().verb().to_string();
+        });
+        // one event for opening the connection and one per selected word
+        assert_eq!(counter.events.load(Ordering::SeqCst), 4);
+        assert_eq!(counter.spans.load(Ordering::SeqCst), 3);
+
+        // a run of one word type is selected in a batch, still one event per word
+        counter.events.store(0, Ordering::SeqCst);
+        tracing::subscriber::with_default(counter, || {
+            FluentCodes::default()
+                .with_seed(3)
+                .adjective()
+                .adjective()
+                .adjective()
+                .to_string();
+        });
+        assert_eq!(counter.events.load(Ordering::SeqCst), 4);
+    }
 }
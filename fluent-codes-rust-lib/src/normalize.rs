@@ -0,0 +1,42 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
+
+/// Unicode normalization form applied to the components of a code,
+/// see https://unicode.org/reports/tr15/. Forms are only applied with the
+/// `unicode-normalization` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Form {
+    /// Canonical composition, `é` as one code point
+    Nfc,
+    /// Canonical decomposition, `é` as `e` and a combining accent
+    Nfd,
+    /// Compatibility composition, also replacing ligatures such as `ﬁ` by `fi`
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+#[cfg(feature = "unicode-normalization")]
+impl Form {
+    /// Normalizes `text` to this form
+    pub(crate) fn apply(&self, text: &str) -> String {
+        match self {
+            Form::Nfc => text.nfc().collect(),
+            Form::Nfd => text.nfd().collect(),
+            Form::Nfkc => text.nfkc().collect(),
+            Form::Nfkd => text.nfkd().collect(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Letter case applied to a whole displayed code, joiners and literals included, after
+/// the per-word [`crate::Case`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputCase {
+    /// `Fluffy-vacuum-x1`, left as assembled
+    #[default]
+    AsIs,
+    /// `fluffy-vacuum-x1`
+    Lower,
+    /// `FLUFFY-VACUUM-X1`
+    Upper,
+}
+
+impl OutputCase {
+    /// Applies the case to a displayed code
+    pub(crate) fn apply(&self, code: &str) -> String {
+        match self {
+            OutputCase::AsIs => code.to_string(),
+            OutputCase::Lower => code.to_lowercase(),
+            OutputCase::Upper => code.to_uppercase(),
+        }
+    }
+}
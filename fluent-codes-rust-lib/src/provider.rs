@@ -0,0 +1,1106 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Word sources used by [`FluentCodes`](crate::FluentCodes).
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::os::raw::c_uint;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use rand::{Rng, RngCore};
+use rusqlite::{ffi, Connection, OpenFlags, OptionalExtension, ToSql};
+
+use crate::{fallback, Asset, FluentCodesError, SelectionMode, WordType};
+
+pub(crate) const DB_PATH: &str = "./db/words_release.db";
+const DB_FILE: &str = "words_release.db";
+
+/// Where a [`SqliteProvider`] found the words it serves
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbSource {
+    /// The database given to [`SqliteProvider::new`]
+    Explicit(PathBuf),
+    /// The database embedded in the library, loaded in memory
+    Embedded,
+    /// A database found at one of the default locations, relative to the working
+    /// directory or to the executable
+    Candidate(PathBuf),
+    /// The embedded fallback list, as no database could be opened
+    Fallback,
+}
+
+/// Source of the words fluent codes are built from
+pub trait WordProvider: Send {
+    /// Returns a random word of the given type whose length is between `min` and `max`
+    fn random_word(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<String, FluentCodesError>;
+
+    /// Returns `k` random words of the given type whose length is between `min` and `max`,
+    /// drawn independently like `k` calls of [`WordProvider::random_word`]. Sources paying
+    /// per query should override it to fetch the words at once.
+    fn random_words(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        k: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Vec<String>, FluentCodesError> {
+        (0..k)
+            .map(|_| self.random_word(wt, min, max, rng))
+            .collect()
+    }
+
+    /// Returns a word of the given type whose length is between `min` and `max`, chosen at
+    /// random or as the shortest or longest one following `mode`
+    fn select_word(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        mode: SelectionMode,
+        rng: &mut dyn RngCore,
+    ) -> Result<String, FluentCodesError> {
+        match mode {
+            SelectionMode::Random => self.random_word(wt, min, max, rng),
+            _ => {
+                let words = self.words_in_range(wt, min, max)?;
+                by_length(words.iter().map(String::as_str), mode)
+                    .ok_or(FluentCodesError::NoWordsInRange(wt, min, max))
+            }
+        }
+    }
+
+    /// Returns a stable identifier for `word`, used to encode tokens;
+    /// providers without identifiers return `None` and the word is stored as text
+    fn word_id(&mut self, _wt: WordType, _word: &str) -> Result<Option<u64>, FluentCodesError> {
+        Ok(None)
+    }
+
+    /// Returns the word previously identified by [`WordProvider::word_id`]
+    fn word_by_id(&mut self, _wt: WordType, _id: u64) -> Result<Option<String>, FluentCodesError> {
+        Ok(None)
+    }
+
+    /// Returns the shortest and longest word length of the given type
+    fn length_bounds(&mut self, _wt: WordType) -> Result<(usize, usize), FluentCodesError> {
+        Err(FluentCodesError::Unsupported("length_bounds"))
+    }
+
+    /// Returns how many words of the given type have a length between `min` and `max`
+    fn count(&mut self, _wt: WordType, _min: usize, _max: usize) -> Result<u64, FluentCodesError> {
+        Err(FluentCodesError::Unsupported("count"))
+    }
+
+    /// Returns the number of words of the given type of each length
+    fn length_histogram(
+        &mut self,
+        _wt: WordType,
+    ) -> Result<BTreeMap<usize, u64>, FluentCodesError> {
+        Err(FluentCodesError::Unsupported("length_histogram"))
+    }
+
+    /// Returns every word of the given type whose length is between `min` and `max`
+    fn words_in_range(
+        &mut self,
+        _wt: WordType,
+        _min: usize,
+        _max: usize,
+    ) -> Result<Vec<String>, FluentCodesError> {
+        Err(FluentCodesError::Unsupported("words_in_range"))
+    }
+
+    /// Whether `word`, in lowercase, is a word of the given type
+    fn contains(&mut self, _wt: WordType, _word: &str) -> Result<bool, FluentCodesError> {
+        Err(FluentCodesError::Unsupported("contains"))
+    }
+
+    /// Restricts the words selected, counted and listed by length to those matching the SQL
+    /// `predicate`, or lifts the restriction with `None`
+    fn set_sql_predicate(&mut self, _predicate: Option<String>) -> Result<(), FluentCodesError> {
+        Err(FluentCodesError::Unsupported("sql_predicate"))
+    }
+
+    /// Restricts the words selected, counted and listed by length to those whose lowercase
+    /// form matches the SQL `GLOB` `pattern`, or lifts the restriction with `None`
+    fn set_sql_like(&mut self, _pattern: Option<String>) -> Result<(), FluentCodesError> {
+        Err(FluentCodesError::Unsupported("sql_like"))
+    }
+
+    /// Runs a custom SQL query returning a single row with a single text column, on the
+    /// source's own connection
+    fn query_one(
+        &mut self,
+        _sql: &str,
+        _params: &[&dyn ToSql],
+    ) -> Result<String, FluentCodesError> {
+        Err(FluentCodesError::Unsupported("query_one"))
+    }
+
+    /// Whether words are served from the embedded fallback list
+    fn is_using_fallback(&self) -> bool {
+        false
+    }
+
+    /// Where the words are served from, `None` for sources that are not a word database or
+    /// before the database is first queried
+    fn db_source(&self) -> Option<DbSource> {
+        None
+    }
+
+    /// Picks one item uniformly from `items` in a single pass, `None` when it is empty.
+    ///
+    /// Meant for [`WordProvider::random_word`] of providers backed by a stream of unknown
+    /// length. Sources that can count their words should prefer offset sampling, a count
+    /// followed by the word at a random offset, which does not read every word.
+    fn reservoir_sample<I: IntoIterator>(items: I, rng: &mut dyn RngCore) -> Option<I::Item>
+    where
+        Self: Sized,
+    {
+        let mut chosen = None;
+        for (seen, item) in items.into_iter().enumerate() {
+            if rng.gen_range(0..=seen) == 0 {
+                chosen = Some(item);
+            }
+        }
+        chosen
+    }
+}
+
+fn histogram<'a>(words: impl Iterator<Item = &'a str>) -> BTreeMap<usize, u64> {
+    let mut histogram = BTreeMap::new();
+    for word in words {
+        *histogram.entry(word.chars().count()).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// The shortest or, with [`SelectionMode::Longest`], the longest of `words`, the first one
+/// among words of the same length
+fn by_length<'a>(words: impl Iterator<Item = &'a str>, mode: SelectionMode) -> Option<String> {
+    let mut chosen: Option<(usize, &str)> = None;
+    for word in words {
+        let len = word.chars().count();
+        let better = chosen.is_none_or(|(best, _)| match mode {
+            SelectionMode::Longest => len > best,
+            _ => len < best,
+        });
+        if better {
+            chosen = Some((len, word));
+        }
+    }
+    chosen.map(|(_, word)| word.to_string())
+}
+
+fn bounds<'a>(
+    wt: WordType,
+    words: impl Iterator<Item = &'a str>,
+) -> Result<(usize, usize), FluentCodesError> {
+    words
+        .map(|word| word.chars().count())
+        .fold(None, |bounds, len| match bounds {
+            None => Some((len, len)),
+            Some((min, max)) => Some((min.min(len), max.max(len))),
+        })
+        .ok_or(FluentCodesError::EmptyTable(wt))
+}
+
+/// Returns `table` when it is a plain SQL identifier matching `[A-Za-z_][A-Za-z0-9_]*`, so
+/// it can be interpolated into a query, and fails with
+/// [`FluentCodesError::InvalidTableName`] otherwise
+pub(crate) fn checked_table(table: &str) -> Result<&str, FluentCodesError> {
+    let mut chars = table.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match valid {
+        true => Ok(table),
+        false => Err(FluentCodesError::InvalidTableName(table.to_string())),
+    }
+}
+
+/// Opens the database at `path` read-only and checks that it can be read
+fn open_checked(path: &Path) -> Result<Connection, FluentCodesError> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    connection.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })?;
+    Ok(connection)
+}
+
+/// The bytes of the embedded database, kept for the whole process so connections can read
+/// them in place, `None` when it is missing
+fn embedded_bytes() -> Option<&'static [u8]> {
+    static BYTES: OnceLock<Option<Cow<'static, [u8]>>> = OnceLock::new();
+    BYTES
+        .get_or_init(|| Asset::get(DB_FILE).map(|asset| asset.data))
+        .as_deref()
+}
+
+/// Opens an in-memory connection whose database is read from `bytes` without copying them,
+/// `None` when they do not hold a readable database
+fn deserialize(bytes: &'static [u8]) -> Option<Connection> {
+    let connection = Connection::open_in_memory().ok()?;
+    let size = i64::try_from(bytes.len()).ok()?;
+    // SAFETY: the handle belongs to the open connection and `bytes` lives for the whole
+    // process. A read-only deserialized database is never written, resized or freed by SQLite.
+    let rc = unsafe {
+        ffi::sqlite3_deserialize(
+            connection.handle(),
+            c"main".as_ptr(),
+            bytes.as_ptr().cast_mut(),
+            size,
+            size,
+            ffi::SQLITE_DESERIALIZE_READONLY as c_uint,
+        )
+    };
+    if rc != ffi::SQLITE_OK {
+        return None;
+    }
+    connection
+        .query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .ok()?;
+    Some(connection)
+}
+
+/// Opens a connection to the embedded database, `None` when it cannot be loaded. Every
+/// connection reads the same bytes in place, so opening one copies nothing.
+fn open_embedded() -> Option<Connection> {
+    deserialize(embedded_bytes()?)
+}
+
+/// Selects words from the SQLite word database, or from the embedded fallback list
+/// when the database cannot be opened.
+///
+/// A provider created with [`SqliteProvider::new`] only tries the given path. The default
+/// one tries, in order, the database embedded in the library, `./db/words_release.db`
+/// and `db/words_release.db` next to the executable, so it works from any working
+/// directory. [`SqliteProvider::db_source`] tells which one was opened.
+#[derive(Debug)]
+pub struct SqliteProvider {
+    sources: Vec<DbSource>,
+    source: Option<DbSource>,
+    connection: Option<Connection>,
+    predicate: Option<String>,
+    glob: Option<String>,
+}
+
+impl Default for SqliteProvider {
+    fn default() -> Self {
+        let mut sources = vec![DbSource::Embedded, DbSource::Candidate(DB_PATH.into())];
+        if let Some(dir) = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        {
+            sources.push(DbSource::Candidate(dir.join("db").join(DB_FILE)));
+        }
+        SqliteProvider::with_sources(sources)
+    }
+}
+
+impl SqliteProvider {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        SqliteProvider::with_sources(vec![DbSource::Explicit(path.as_ref().to_path_buf())])
+    }
+    /// Tries `sources` in order on the first query, falling back to the embedded list
+    /// when none of them opens
+    pub(crate) fn with_sources(sources: Vec<DbSource>) -> Self {
+        SqliteProvider {
+            sources,
+            source: None,
+            connection: None,
+            predicate: None,
+            glob: None,
+        }
+    }
+    /// Opens the database at `path` immediately, failing instead of falling back to the
+    /// embedded list when it cannot be read
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FluentCodesError> {
+        let source = DbSource::Explicit(path.as_ref().to_path_buf());
+        Ok(SqliteProvider {
+            connection: Some(open_checked(path.as_ref())?),
+            sources: vec![source.clone()],
+            source: Some(source),
+            predicate: None,
+            glob: None,
+        })
+    }
+    /// Where the words are served from, `None` until the database is first queried
+    pub fn db_source(&self) -> Option<&DbSource> {
+        self.source.as_ref()
+    }
+    /// Only selects, counts and lists by length the words matching the SQL `predicate`, e.g.
+    /// `word NOT LIKE '%x%'`, appended to the queries as `AND (<predicate>)`.
+    ///
+    /// The predicate is inserted into the SQL verbatim, so it must be trusted input: a
+    /// predicate built from user input allows SQL injection. The embedded fallback list
+    /// ignores it.
+    pub fn with_sql_predicate(mut self, predicate: impl Into<String>) -> Self {
+        self.predicate = Some(predicate.into());
+        self
+    }
+    /// Only selects, counts and lists by length the words whose lowercase form matches the
+    /// `GLOB` `pattern`, e.g. `*[aeiou]` for words ending in a vowel, filtered by the
+    /// database. The pattern is quoted, so unlike [`SqliteProvider::with_sql_predicate`]
+    /// it may come from untrusted input. The embedded fallback list ignores it.
+    pub fn with_sql_like(mut self, pattern: impl Into<String>) -> Self {
+        self.glob = Some(pattern.into());
+        self
+    }
+    /// `FROM` and `where` clauses of the words of a table in a length range, without a
+    /// length condition for the full range `0..=usize::MAX`
+    fn filter(&self, wt: WordType, min: usize, max: usize) -> Result<String, FluentCodesError> {
+        let table = checked_table(wt.table())?;
+        let mut conditions = vec![];
+        if (min, max) != (0, usize::MAX) {
+            conditions.push(format!("length(word) between {} and {}", min, max));
+        }
+        if let Some(predicate) = &self.predicate {
+            conditions.push(format!("({})", predicate));
+        }
+        if let Some(glob) = &self.glob {
+            conditions.push(format!("LOWER(word) GLOB '{}'", glob.replace('\'', "''")));
+        }
+        Ok(match conditions.is_empty() {
+            true => format!("FROM {}", table),
+            false => format!("FROM {} where {}", table, conditions.join(" AND ")),
+        })
+    }
+    /// Opens the first of the sources that can be read, failing with the error of the last
+    /// one tried when none can
+    fn resolve(&self) -> Result<(DbSource, Connection), FluentCodesError> {
+        let mut last_err = FluentCodesError::Database(rusqlite::Error::InvalidPath(DB_FILE.into()));
+        for source in &self.sources {
+            let path = match source {
+                DbSource::Explicit(path) | DbSource::Candidate(path) => path.as_path(),
+                DbSource::Embedded => match open_embedded() {
+                    Some(connection) => return Ok((source.clone(), connection)),
+                    None => continue,
+                },
+                DbSource::Fallback => continue,
+            };
+            match open_checked(path) {
+                Ok(connection) => return Ok((source.clone(), connection)),
+                Err(err) => last_err = err,
+            }
+        }
+        Err(last_err)
+    }
+    /// Opens the first of the sources that can be read immediately, failing instead of
+    /// falling back to the embedded list when none can
+    pub(crate) fn connect(mut self) -> Result<Self, FluentCodesError> {
+        let (source, connection) = self.resolve()?;
+        self.source = Some(source);
+        self.connection = Some(connection);
+        Ok(self)
+    }
+    fn connection_check(&mut self) -> Option<&Connection> {
+        if self.source.is_none() {
+            let source = match self.resolve() {
+                Ok((source, connection)) => {
+                    self.connection = Some(connection);
+                    source
+                }
+                Err(_) => DbSource::Fallback,
+            };
+            #[cfg(feature = "tracing")]
+            tracing::debug!(source = ?source, "opened word database");
+            self.source = Some(source);
+        }
+        self.connection.as_ref()
+    }
+}
+
+impl WordProvider for SqliteProvider {
+    fn random_word(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<String, FluentCodesError> {
+        let filter = self.filter(wt, min, max)?;
+        let connection = match self.connection_check() {
+            Some(connection) => connection,
+            None => {
+                let candidates: Vec<&str> = fallback::words(wt)
+                    .iter()
+                    .filter(|word| (min..=max).contains(&(word.len())))
+                    .copied()
+                    .collect();
+                if candidates.is_empty() {
+                    return Err(FluentCodesError::NoWordsInRange(wt, min, max));
+                }
+                return Ok(candidates[rng.gen_range(0..candidates.len())].to_string());
+            }
+        };
+        let count: u64 =
+            connection.query_row(&format!("SELECT COUNT(*) {}", filter), [], |row| row.get(0))?;
+        if count == 0 {
+            return Err(FluentCodesError::NoWordsInRange(wt, min, max));
+        }
+        let offset = rng.gen_range(0..count);
+        let sql = format!(
+            "SELECT LOWER(word) {} ORDER BY rowid LIMIT 1 OFFSET ?1",
+            filter
+        );
+        Ok(connection.query_row(&sql, [offset], |row| row.get(0))?)
+    }
+
+    /// Counts the words in range once and selects all `k` offsets in a single query
+    fn random_words(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        k: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Vec<String>, FluentCodesError> {
+        if k == 0 {
+            return Ok(vec![]);
+        }
+        let filter = self.filter(wt, min, max)?;
+        let Some(connection) = self.connection_check() else {
+            return (0..k)
+                .map(|_| self.random_word(wt, min, max, rng))
+                .collect();
+        };
+        let count: u64 =
+            connection.query_row(&format!("SELECT COUNT(*) {}", filter), [], |row| row.get(0))?;
+        if count == 0 {
+            return Err(FluentCodesError::NoWordsInRange(wt, min, max));
+        }
+        let offsets: Vec<u64> = (0..k).map(|_| rng.gen_range(0..count)).collect();
+        let sql = (1..=k)
+            .map(|n| {
+                format!(
+                    "SELECT * FROM (SELECT LOWER(word) {} ORDER BY rowid LIMIT 1 OFFSET ?{})",
+                    filter, n
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" UNION ALL ");
+        let mut statement = connection.prepare(&sql)?;
+        let words = statement.query_map(rusqlite::params_from_iter(offsets), |row| row.get(0))?;
+        Ok(words.collect::<Result<_, _>>()?)
+    }
+
+    /// Selects the shortest or longest word with `ORDER BY length(word) LIMIT 1`
+    fn select_word(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        mode: SelectionMode,
+        rng: &mut dyn RngCore,
+    ) -> Result<String, FluentCodesError> {
+        let order = match mode {
+            SelectionMode::Random => return self.random_word(wt, min, max, rng),
+            SelectionMode::Shortest => "ASC",
+            SelectionMode::Longest => "DESC",
+        };
+        let filter = self.filter(wt, min, max)?;
+        let Some(connection) = self.connection_check() else {
+            let candidates = fallback::words(wt)
+                .iter()
+                .filter(|word| (min..=max).contains(&(word.len())));
+            return by_length(candidates.copied(), mode)
+                .ok_or(FluentCodesError::NoWordsInRange(wt, min, max));
+        };
+        let sql = format!(
+            "SELECT LOWER(word) {} ORDER BY length(word) {} LIMIT 1",
+            filter, order
+        );
+        connection
+            .query_row(&sql, [], |row| row.get(0))
+            .optional()?
+            .ok_or(FluentCodesError::NoWordsInRange(wt, min, max))
+    }
+
+    fn word_id(&mut self, wt: WordType, word: &str) -> Result<Option<u64>, FluentCodesError> {
+        let table = checked_table(wt.table())?;
+        let Some(connection) = self.connection_check() else {
+            return Ok(None);
+        };
+        let sql = format!(
+            "SELECT rowid FROM {} where LOWER(word) = ?1 ORDER BY rowid LIMIT 1",
+            table
+        );
+        Ok(connection
+            .query_row(&sql, [word], |row| row.get(0))
+            .optional()?)
+    }
+
+    fn word_by_id(&mut self, wt: WordType, id: u64) -> Result<Option<String>, FluentCodesError> {
+        let table = checked_table(wt.table())?;
+        let Some(connection) = self.connection_check() else {
+            return Ok(None);
+        };
+        let sql = format!("SELECT LOWER(word) FROM {} where rowid = ?1", table);
+        Ok(connection
+            .query_row(&sql, [id], |row| row.get(0))
+            .optional()?)
+    }
+
+    fn length_bounds(&mut self, wt: WordType) -> Result<(usize, usize), FluentCodesError> {
+        let table = checked_table(wt.table())?;
+        let Some(connection) = self.connection_check() else {
+            return bounds(wt, fallback::words(wt).iter().copied());
+        };
+        let sql = format!("SELECT MIN(length(word)), MAX(length(word)) FROM {}", table);
+        let (min, max): (Option<usize>, Option<usize>) =
+            connection.query_row(&sql, [], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        min.zip(max).ok_or(FluentCodesError::EmptyTable(wt))
+    }
+
+    fn count(&mut self, wt: WordType, min: usize, max: usize) -> Result<u64, FluentCodesError> {
+        let filter = self.filter(wt, min, max)?;
+        let Some(connection) = self.connection_check() else {
+            return Ok(fallback::words(wt)
+                .iter()
+                .filter(|word| (min..=max).contains(&(word.len())))
+                .count() as u64);
+        };
+        let sql = format!("SELECT COUNT(*) {}", filter);
+        Ok(connection.query_row(&sql, [], |row| row.get(0))?)
+    }
+
+    fn length_histogram(&mut self, wt: WordType) -> Result<BTreeMap<usize, u64>, FluentCodesError> {
+        let filter = self.filter(wt, 0, usize::MAX)?;
+        let Some(connection) = self.connection_check() else {
+            return Ok(histogram(fallback::words(wt).iter().copied()));
+        };
+        let sql = format!(
+            "SELECT length(word), COUNT(*) {} GROUP BY length(word)",
+            filter
+        );
+        let mut statement = connection.prepare(&sql)?;
+        let counts = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(counts.collect::<Result<_, _>>()?)
+    }
+
+    fn words_in_range(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+    ) -> Result<Vec<String>, FluentCodesError> {
+        let filter = self.filter(wt, min, max)?;
+        let Some(connection) = self.connection_check() else {
+            return Ok(fallback::words(wt)
+                .iter()
+                .filter(|word| (min..=max).contains(&(word.len())))
+                .map(|word| word.to_string())
+                .collect());
+        };
+        let sql = format!("SELECT LOWER(word) {}", filter);
+        let mut statement = connection.prepare(&sql)?;
+        let words = statement.query_map([], |row| row.get(0))?;
+        Ok(words.collect::<Result<_, _>>()?)
+    }
+
+    fn contains(&mut self, wt: WordType, word: &str) -> Result<bool, FluentCodesError> {
+        if self.connection_check().is_none() {
+            return Ok(fallback::words(wt).contains(&word));
+        }
+        Ok(self.word_id(wt, word)?.is_some())
+    }
+
+    fn set_sql_predicate(&mut self, predicate: Option<String>) -> Result<(), FluentCodesError> {
+        self.predicate = predicate;
+        Ok(())
+    }
+
+    fn set_sql_like(&mut self, pattern: Option<String>) -> Result<(), FluentCodesError> {
+        self.glob = pattern;
+        Ok(())
+    }
+
+    /// Fails with [`FluentCodesError::Unsupported`] when serving the embedded fallback list
+    fn query_one(&mut self, sql: &str, params: &[&dyn ToSql]) -> Result<String, FluentCodesError> {
+        let Some(connection) = self.connection_check() else {
+            return Err(FluentCodesError::Unsupported("query_one"));
+        };
+        Ok(connection.query_row(sql, params, |row| row.get(0))?)
+    }
+
+    fn is_using_fallback(&self) -> bool {
+        self.source == Some(DbSource::Fallback)
+    }
+
+    fn db_source(&self) -> Option<DbSource> {
+        self.source.clone()
+    }
+}
+
+/// Selects words from lists held in memory
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryProvider {
+    words: HashMap<WordType, Vec<String>>,
+}
+
+impl InMemoryProvider {
+    /// Creates a provider serving the given words of each type
+    pub fn new(words: HashMap<WordType, Vec<String>>) -> Self {
+        InMemoryProvider { words }
+    }
+    /// Adds words of the given type
+    pub fn with_words(mut self, wt: WordType, words: Vec<String>) -> Self {
+        self.words.entry(wt).or_default().extend(words);
+        self
+    }
+}
+
+impl WordProvider for InMemoryProvider {
+    fn random_word(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<String, FluentCodesError> {
+        let candidates: Vec<&String> = self
+            .words
+            .get(&wt)
+            .into_iter()
+            .flatten()
+            .filter(|word| (min..=max).contains(&(word.chars().count())))
+            .collect();
+        if candidates.is_empty() {
+            return Err(FluentCodesError::NoWordsInRange(wt, min, max));
+        }
+        Ok(candidates[rng.gen_range(0..candidates.len())].clone())
+    }
+
+    fn count(&mut self, wt: WordType, min: usize, max: usize) -> Result<u64, FluentCodesError> {
+        Ok(self
+            .words
+            .get(&wt)
+            .into_iter()
+            .flatten()
+            .filter(|word| (min..=max).contains(&(word.chars().count())))
+            .count() as u64)
+    }
+
+    fn length_histogram(&mut self, wt: WordType) -> Result<BTreeMap<usize, u64>, FluentCodesError> {
+        Ok(histogram(
+            self.words
+                .get(&wt)
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        ))
+    }
+
+    fn length_bounds(&mut self, wt: WordType) -> Result<(usize, usize), FluentCodesError> {
+        bounds(
+            wt,
+            self.words
+                .get(&wt)
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        )
+    }
+
+    fn words_in_range(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+    ) -> Result<Vec<String>, FluentCodesError> {
+        Ok(self
+            .words
+            .get(&wt)
+            .into_iter()
+            .flatten()
+            .filter(|word| (min..=max).contains(&(word.chars().count())))
+            .cloned()
+            .collect())
+    }
+
+    fn contains(&mut self, wt: WordType, word: &str) -> Result<bool, FluentCodesError> {
+        Ok(self
+            .words
+            .get(&wt)
+            .is_some_and(|words| words.iter().any(|candidate| candidate == word)))
+    }
+}
+
+/// Queries providers in order and returns the first word found
+pub struct ChainProvider {
+    providers: Vec<Box<dyn WordProvider>>,
+}
+
+impl ChainProvider {
+    pub fn new(providers: Vec<Box<dyn WordProvider>>) -> Self {
+        ChainProvider { providers }
+    }
+    fn first_ok<T>(
+        &mut self,
+        mut query: impl FnMut(&mut dyn WordProvider) -> Result<T, FluentCodesError>,
+    ) -> Result<T, FluentCodesError> {
+        let mut errors = vec![];
+        for provider in self.providers.iter_mut() {
+            match query(provider.as_mut()) {
+                Ok(value) => return Ok(value),
+                Err(err) => errors.push(err),
+            }
+        }
+        Err(FluentCodesError::AllProvidersFailed(errors))
+    }
+    /// Runs `update` on every provider, failing only when it fails on all of them
+    fn every_ok(
+        &mut self,
+        mut update: impl FnMut(&mut dyn WordProvider) -> Result<(), FluentCodesError>,
+    ) -> Result<(), FluentCodesError> {
+        let mut errors = vec![];
+        for provider in self.providers.iter_mut() {
+            if let Err(err) = update(provider.as_mut()) {
+                errors.push(err);
+            }
+        }
+        match errors.len() == self.providers.len() {
+            true => Err(FluentCodesError::AllProvidersFailed(errors)),
+            false => Ok(()),
+        }
+    }
+}
+
+impl WordProvider for ChainProvider {
+    fn random_word(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<String, FluentCodesError> {
+        self.first_ok(|provider| provider.random_word(wt, min, max, rng))
+    }
+
+    fn random_words(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        k: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Vec<String>, FluentCodesError> {
+        self.first_ok(|provider| provider.random_words(wt, min, max, k, rng))
+    }
+
+    fn select_word(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+        mode: SelectionMode,
+        rng: &mut dyn RngCore,
+    ) -> Result<String, FluentCodesError> {
+        self.first_ok(|provider| provider.select_word(wt, min, max, mode, rng))
+    }
+
+    fn length_bounds(&mut self, wt: WordType) -> Result<(usize, usize), FluentCodesError> {
+        self.first_ok(|provider| provider.length_bounds(wt))
+    }
+
+    fn words_in_range(
+        &mut self,
+        wt: WordType,
+        min: usize,
+        max: usize,
+    ) -> Result<Vec<String>, FluentCodesError> {
+        self.first_ok(|provider| provider.words_in_range(wt, min, max))
+    }
+
+    /// Count of the first provider that has words in range, as that is the one selecting
+    fn count(&mut self, wt: WordType, min: usize, max: usize) -> Result<u64, FluentCodesError> {
+        let mut errors = vec![];
+        for provider in self.providers.iter_mut() {
+            match provider.count(wt, min, max) {
+                Ok(0) => {}
+                Ok(count) => return Ok(count),
+                Err(err) => errors.push(err),
+            }
+        }
+        if !errors.is_empty() && errors.len() == self.providers.len() {
+            return Err(FluentCodesError::AllProvidersFailed(errors));
+        }
+        Ok(0)
+    }
+
+    /// Id of the first provider identifying the word, combined with the index of that
+    /// provider so [`WordProvider::word_by_id`] asks the same one
+    fn word_id(&mut self, wt: WordType, word: &str) -> Result<Option<u64>, FluentCodesError> {
+        let providers = self.providers.len() as u64;
+        for (index, provider) in self.providers.iter_mut().enumerate() {
+            if let Ok(Some(id)) = provider.word_id(wt, word) {
+                return Ok(id
+                    .checked_mul(providers)
+                    .and_then(|id| id.checked_add(index as u64)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn word_by_id(&mut self, wt: WordType, id: u64) -> Result<Option<String>, FluentCodesError> {
+        let providers = self.providers.len() as u64;
+        if providers == 0 {
+            return Ok(None);
+        }
+        self.providers[(id % providers) as usize].word_by_id(wt, id / providers)
+    }
+
+    /// Histogram of the first provider that has words of the type, as that is the one
+    /// selecting
+    fn length_histogram(&mut self, wt: WordType) -> Result<BTreeMap<usize, u64>, FluentCodesError> {
+        let mut errors = vec![];
+        for provider in self.providers.iter_mut() {
+            match provider.length_histogram(wt) {
+                Ok(histogram) if histogram.values().all(|count| *count == 0) => {}
+                Ok(histogram) => return Ok(histogram),
+                Err(err) => errors.push(err),
+            }
+        }
+        if !errors.is_empty() && errors.len() == self.providers.len() {
+            return Err(FluentCodesError::AllProvidersFailed(errors));
+        }
+        Ok(BTreeMap::new())
+    }
+
+    /// Restricts every provider that supports it, so no provider selects other words
+    fn set_sql_predicate(&mut self, predicate: Option<String>) -> Result<(), FluentCodesError> {
+        self.every_ok(|provider| provider.set_sql_predicate(predicate.clone()))
+    }
+
+    /// Restricts every provider that supports it, so no provider selects other words
+    fn set_sql_like(&mut self, pattern: Option<String>) -> Result<(), FluentCodesError> {
+        self.every_ok(|provider| provider.set_sql_like(pattern.clone()))
+    }
+
+    fn query_one(&mut self, sql: &str, params: &[&dyn ToSql]) -> Result<String, FluentCodesError> {
+        self.first_ok(|provider| provider.query_one(sql, params))
+    }
+
+    fn contains(&mut self, wt: WordType, word: &str) -> Result<bool, FluentCodesError> {
+        let mut errors = vec![];
+        for provider in self.providers.iter_mut() {
+            match provider.contains(wt, word) {
+                Ok(false) => {}
+                Ok(true) => return Ok(true),
+                Err(err) => errors.push(err),
+            }
+        }
+        if !errors.is_empty() && errors.len() == self.providers.len() {
+            return Err(FluentCodesError::AllProvidersFailed(errors));
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::{checked_table, open_embedded, DB_PATH};
+    use crate::{
+        ChainProvider, DbSource, FluentCodesError, InMemoryProvider, SqliteProvider, WordProvider,
+        WordType,
+    };
+
+    #[test]
+    fn in_memory_length_filter() {
+        let words = ["fox", "otter", "badger", "dolphin"].map(String::from);
+        let mut provider = InMemoryProvider::new(HashMap::from([(WordType::Noun, words.to_vec())]));
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            let word = provider
+                .random_word(WordType::Noun, 5, 6, &mut rng)
+                .unwrap();
+            assert!(word == "otter" || word == "badger");
+        }
+        assert!(matches!(
+            provider.random_word(WordType::Noun, 8, 10, &mut rng),
+            Err(FluentCodesError::NoWordsInRange(WordType::Noun, 8, 10))
+        ));
+        assert!(matches!(
+            provider.random_word(WordType::Verb, 1, 10, &mut rng),
+            Err(FluentCodesError::NoWordsInRange(WordType::Verb, 1, 10))
+        ));
+    }
+
+    #[test]
+    fn reservoir_sample_uniform() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut counts = [0; 5];
+        for _ in 0..10_000 {
+            let item = InMemoryProvider::reservoir_sample(0..5, &mut rng).unwrap();
+            counts[item] += 1;
+        }
+        assert!(counts.iter().all(|count| (1800..=2200).contains(count)));
+        assert_eq!(
+            InMemoryProvider::reservoir_sample(std::iter::empty::<u8>(), &mut rng),
+            None
+        );
+    }
+
+    #[test]
+    fn chain_falls_back() {
+        let nouns = InMemoryProvider::default().with_words(WordType::Noun, vec!["otter".into()]);
+        let verbs = InMemoryProvider::default().with_words(WordType::Verb, vec!["wander".into()]);
+        let mut chain = ChainProvider::new(vec![Box::new(nouns), Box::new(verbs)]);
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(
+            chain.random_word(WordType::Noun, 1, 10, &mut rng).unwrap(),
+            "otter"
+        );
+        assert_eq!(
+            chain.random_word(WordType::Verb, 1, 10, &mut rng).unwrap(),
+            "wander"
+        );
+        match chain.random_word(WordType::Adjective, 1, 10, &mut rng) {
+            Err(FluentCodesError::AllProvidersFailed(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn chain_forwards() {
+        let verbs = InMemoryProvider::default().with_words(WordType::Verb, vec!["wander".into()]);
+        let mut chain =
+            ChainProvider::new(vec![Box::new(verbs), Box::new(SqliteProvider::default())]);
+        let mut rng = StdRng::seed_from_u64(1);
+        let noun = chain.random_word(WordType::Noun, 4, 8, &mut rng).unwrap();
+        let id = chain.word_id(WordType::Noun, &noun).unwrap().unwrap();
+        assert_eq!(id % 2, 1);
+        assert_eq!(chain.word_by_id(WordType::Noun, id).unwrap(), Some(noun));
+        assert_eq!(
+            chain.length_histogram(WordType::Verb).unwrap(),
+            [(6, 1)].into()
+        );
+        assert!(chain.length_histogram(WordType::Noun).unwrap().len() > 1);
+        chain.set_sql_like(Some("o*".into())).unwrap();
+        for _ in 0..10 {
+            let noun = chain.random_word(WordType::Noun, 1, 20, &mut rng).unwrap();
+            assert!(noun.to_lowercase().starts_with('o'));
+        }
+        chain
+            .set_sql_predicate(Some("length(word) = 5".into()))
+            .unwrap();
+        let noun = chain.random_word(WordType::Noun, 1, 20, &mut rng).unwrap();
+        assert_eq!(noun.chars().count(), 5);
+        assert_eq!(chain.query_one("SELECT 'x'", &[]).unwrap(), "x");
+        let mut words = ChainProvider::new(vec![Box::new(InMemoryProvider::default())]);
+        assert!(matches!(
+            words.set_sql_like(Some("o*".into())),
+            Err(FluentCodesError::AllProvidersFailed(_))
+        ));
+    }
+
+    #[test]
+    fn table_names_checked() {
+        assert_eq!(checked_table("noun").unwrap(), "noun");
+        assert_eq!(checked_table("_Tbl_2").unwrap(), "_Tbl_2");
+        for malicious in ["noun; DROP TABLE noun", "noun--", "2noun", "", "nøun"] {
+            assert!(matches!(
+                checked_table(malicious),
+                Err(FluentCodesError::InvalidTableName(name)) if name == malicious
+            ));
+        }
+    }
+
+    #[test]
+    fn sqlite_words_at_once() {
+        let mut provider = SqliteProvider::default();
+        let mut rng = StdRng::seed_from_u64(6);
+        let words = provider
+            .random_words(WordType::Noun, 4, 7, 5, &mut rng)
+            .unwrap();
+        assert_eq!(words.len(), 5);
+        assert!(words.iter().all(|word| (4..=7).contains(&word.len())));
+        let mut rng = StdRng::seed_from_u64(6);
+        assert_eq!(
+            provider
+                .random_words(WordType::Noun, 4, 7, 5, &mut rng)
+                .unwrap(),
+            words
+        );
+        assert!(matches!(
+            provider.random_words(WordType::Noun, 90, 99, 2, &mut rng),
+            Err(FluentCodesError::NoWordsInRange(WordType::Noun, 90, 99))
+        ));
+    }
+
+    #[test]
+    fn sqlite_words_in_range_lowercase() {
+        let mut provider = SqliteProvider::default();
+        let names = provider.words_in_range(WordType::ProperNoun, 3, 8).unwrap();
+        assert!(!names.is_empty());
+        assert!(names.iter().all(|name| *name == name.to_lowercase()));
+    }
+
+    #[test]
+    fn embedded_db_in_memory() {
+        let mut provider = SqliteProvider::default();
+        assert!(provider.count(WordType::Noun, 0, usize::MAX).unwrap() > 0);
+        assert_eq!(provider.db_source(), Some(&DbSource::Embedded));
+        let (first, second) = (open_embedded().unwrap(), open_embedded().unwrap());
+        assert_eq!(first.path(), Some(""));
+        assert!(first.execute("CREATE TABLE written (x)", []).is_err());
+        let words = |connection: &rusqlite::Connection| {
+            connection
+                .query_row("SELECT COUNT(*) FROM noun", [], |row| row.get::<_, i64>(0))
+                .unwrap()
+        };
+        assert_eq!(words(&first), words(&second));
+    }
+
+    #[test]
+    fn db_resolution_order() {
+        let resolved = |mut provider: SqliteProvider| {
+            assert_eq!(provider.db_source(), None);
+            provider.count(WordType::Noun, 0, usize::MAX).unwrap();
+            provider.db_source().cloned().unwrap()
+        };
+        assert_eq!(
+            resolved(SqliteProvider::new(DB_PATH)),
+            DbSource::Explicit(DB_PATH.into())
+        );
+        assert_eq!(resolved(SqliteProvider::default()), DbSource::Embedded);
+        let candidates = vec![
+            DbSource::Candidate("./db/missing.db".into()),
+            DbSource::Candidate(DB_PATH.into()),
+        ];
+        assert_eq!(
+            resolved(SqliteProvider::with_sources(candidates)),
+            DbSource::Candidate(DB_PATH.into())
+        );
+        let missing = SqliteProvider::new("./db/missing.db");
+        assert_eq!(resolved(missing), DbSource::Fallback);
+        let connected = SqliteProvider::default().connect().unwrap();
+        assert_eq!(connected.db_source(), Some(&DbSource::Embedded));
+        assert!(SqliteProvider::new("./db/missing.db").connect().is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compact base62 encoding of code components.
+//!
+//! Every component is written as a one character tag, a payload length and the payload,
+//! all using the base62 alphabet. Tags below [`TEXT_TAG`] are indices into
+//! [`WordType::ALL`] with the word's provider id as payload. [`TEXT_TAG`] stores a
+//! component verbatim: a two character byte count followed by two characters per byte.
+//! Components of more bytes than two characters can count use [`LONG_TEXT_TAG`], whose
+//! byte count has a length like other payloads. Tags from [`DIGITS_TAG`] up encode a digit
+//! block whose width is `tag - DIGITS_TAG` with the number as payload.
+
+use crate::{FluentCodesError, WordType};
+
+const ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const LONG_TEXT_TAG: usize = 30;
+const TEXT_TAG: usize = 31;
+const DIGITS_TAG: usize = 32;
+
+/// A single encoded component
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Chunk {
+    /// Word type and provider id of a word
+    Word(WordType, u64),
+    /// Component stored verbatim
+    Text(String),
+    /// Width and value of a digit block
+    Digits(usize, u64),
+}
+
+fn encode_number(mut value: u64) -> String {
+    let mut out = vec![];
+    loop {
+        out.push(ALPHABET[(value % 62) as usize]);
+        value /= 62;
+        if value == 0 {
+            break;
+        }
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap()
+}
+
+fn encode_fixed(value: usize, width: usize) -> String {
+    let encoded = encode_number(value as u64);
+    format!("{}{}", "0".repeat(width - encoded.len()), encoded)
+}
+
+fn decode_char(c: u8) -> Option<u64> {
+    ALPHABET.iter().position(|a| *a == c).map(|p| p as u64)
+}
+
+fn decode_number(chars: &[u8]) -> Option<u64> {
+    chars.iter().try_fold(0u64, |acc, c| {
+        acc.checked_mul(62)?.checked_add(decode_char(*c)?)
+    })
+}
+
+pub(crate) fn encode(chunks: &[Chunk]) -> String {
+    let mut token = String::new();
+    for chunk in chunks {
+        let (tag, payload) = match chunk {
+            Chunk::Word(word_type, id) => (word_type.index(), encode_number(*id)),
+            Chunk::Text(text) => {
+                if text.len() < 62 * 62 {
+                    token.push(ALPHABET[TEXT_TAG] as char);
+                    token.push_str(&encode_fixed(text.len(), 2));
+                } else {
+                    let len = encode_number(text.len() as u64);
+                    token.push(ALPHABET[LONG_TEXT_TAG] as char);
+                    token.push(ALPHABET[len.len()] as char);
+                    token.push_str(&len);
+                }
+                for byte in text.bytes() {
+                    token.push_str(&encode_fixed(byte as usize, 2));
+                }
+                continue;
+            }
+            Chunk::Digits(width, value) => (DIGITS_TAG + width, encode_number(*value)),
+        };
+        token.push(ALPHABET[tag] as char);
+        token.push(ALPHABET[payload.len()] as char);
+        token.push_str(&payload);
+    }
+    token
+}
+
+pub(crate) fn decode(token: &str) -> Result<Vec<Chunk>, FluentCodesError> {
+    let invalid = || FluentCodesError::InvalidToken(token.to_string());
+    let bytes = token.as_bytes();
+    let number = |from: usize, len: usize| {
+        bytes
+            .get(from..from + len)
+            .and_then(decode_number)
+            .ok_or_else(invalid)
+    };
+    let text = |from: usize, len: usize| {
+        let text = (0..len)
+            .map(|n| u8::try_from(number(from + n * 2, 2)?).map_err(|_| invalid()))
+            .collect::<Result<Vec<u8>, _>>()?;
+        String::from_utf8(text).map_err(|_| invalid())
+    };
+    let mut chunks = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let tag = number(i, 1)? as usize;
+        if tag == TEXT_TAG || tag == LONG_TEXT_TAG {
+            let (len, from) = match tag {
+                TEXT_TAG => (number(i + 1, 2)? as usize, i + 3),
+                _ => {
+                    let digits = number(i + 1, 1)? as usize;
+                    (number(i + 2, digits)? as usize, i + 2 + digits)
+                }
+            };
+            let end = len
+                .checked_mul(2)
+                .and_then(|n| n.checked_add(from))
+                .filter(|end| *end <= bytes.len())
+                .ok_or_else(invalid)?;
+            chunks.push(Chunk::Text(text(from, len)?));
+            i = end;
+            continue;
+        }
+        let len = number(i + 1, 1)? as usize;
+        let value = number(i + 2, len)?;
+        if tag >= DIGITS_TAG {
+            chunks.push(Chunk::Digits(tag - DIGITS_TAG, value));
+        } else {
+            let word_type = WordType::ALL.get(tag).ok_or_else(invalid)?;
+            chunks.push(Chunk::Word(*word_type, value));
+        }
+        i += 2 + len;
+    }
+    Ok(chunks)
+}
@@ -0,0 +1,191 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::FluentCodesError;
+
+/// Part of speech a word is selected from, see https://universaldependencies.org/u/pos/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WordType {
+    Adjective,
+    Adposition,
+    Adverb,
+    Auxiliary,
+    CoordinatingConjunction,
+    Determiner,
+    Interjection,
+    Noun,
+    Particle,
+    Pronoun,
+    ProperNoun,
+    Punctuation,
+    SubordinatingConjunction,
+    Symbol,
+    Verb,
+}
+
+impl WordType {
+    /// All word types, in table order
+    pub const ALL: [WordType; 15] = [
+        WordType::Adjective,
+        WordType::Adposition,
+        WordType::Adverb,
+        WordType::Auxiliary,
+        WordType::CoordinatingConjunction,
+        WordType::Determiner,
+        WordType::Interjection,
+        WordType::Noun,
+        WordType::Particle,
+        WordType::Pronoun,
+        WordType::ProperNoun,
+        WordType::Punctuation,
+        WordType::SubordinatingConjunction,
+        WordType::Symbol,
+        WordType::Verb,
+    ];
+
+    /// Name of the database table holding words of this type
+    pub fn table(&self) -> &'static str {
+        match self {
+            WordType::Adjective => "adj",
+            WordType::Adposition => "adp",
+            WordType::Adverb => "adv",
+            WordType::Auxiliary => "aux",
+            WordType::CoordinatingConjunction => "cconj",
+            WordType::Determiner => "det",
+            WordType::Interjection => "intj",
+            WordType::Noun => "noun",
+            WordType::Particle => "part",
+            WordType::Pronoun => "pron",
+            WordType::ProperNoun => "propn",
+            WordType::Punctuation => "punct",
+            WordType::SubordinatingConjunction => "sconj",
+            WordType::Symbol => "sym",
+            WordType::Verb => "verb",
+        }
+    }
+
+    /// Word type stored in the given database table
+    pub(crate) fn from_table(table: &str) -> Option<WordType> {
+        WordType::ALL.into_iter().find(|wt| wt.table() == table)
+    }
+
+    /// Whether the type holds real words, rather than punctuation or symbols such as digits
+    pub(crate) fn is_word(&self) -> bool {
+        !matches!(self, WordType::Punctuation | WordType::Symbol)
+    }
+
+    /// Word type of a shorthand letter, case-insensitively:
+    ///
+    /// | letter | word type | letter | word type |
+    /// |---|---|---|---|
+    /// | `A` | adjective | `N` | noun |
+    /// | `P` | adposition | `T` | particle |
+    /// | `R` | adverb | `O` | pronoun |
+    /// | `X` | auxiliary | `M` | proper noun |
+    /// | `C` | coordinating conjunction | `U` | punctuation |
+    /// | `D` | determiner | `S` | subordinating conjunction |
+    /// | `I` | interjection | `Y` | symbol |
+    /// | | | `V` | verb |
+    pub fn from_shorthand(c: char) -> Option<WordType> {
+        let word_type = match c.to_ascii_uppercase() {
+            'A' => WordType::Adjective,
+            'P' => WordType::Adposition,
+            'R' => WordType::Adverb,
+            'X' => WordType::Auxiliary,
+            'C' => WordType::CoordinatingConjunction,
+            'D' => WordType::Determiner,
+            'I' => WordType::Interjection,
+            'N' => WordType::Noun,
+            'T' => WordType::Particle,
+            'O' => WordType::Pronoun,
+            'M' => WordType::ProperNoun,
+            'U' => WordType::Punctuation,
+            'S' => WordType::SubordinatingConjunction,
+            'Y' => WordType::Symbol,
+            'V' => WordType::Verb,
+            _ => return None,
+        };
+        Some(word_type)
+    }
+
+    /// Word types of a string of shorthand letters, e.g. `"AVN"`, see
+    /// [`WordType::from_shorthand`]
+    pub fn parse_shorthand(letters: &str) -> Result<Vec<WordType>, FluentCodesError> {
+        letters
+            .chars()
+            .map(|c| {
+                WordType::from_shorthand(c)
+                    .ok_or_else(|| FluentCodesError::InvalidPattern(letters.to_string()))
+            })
+            .collect()
+    }
+
+    /// Position of this word type in [`WordType::ALL`]
+    pub(crate) fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Writes the table name, e.g. `noun` or `propn`
+impl fmt::Display for WordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.table())
+    }
+}
+
+/// Parses a table name case-insensitively, e.g. `"ADJ"` or `"propn"`
+impl FromStr for WordType {
+    type Err = FluentCodesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WordType::from_table(&s.to_ascii_lowercase())
+            .ok_or_else(|| FluentCodesError::UnknownTable(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FluentCodesError, WordType};
+
+    #[test]
+    fn shorthand_letters() {
+        assert_eq!(
+            WordType::parse_shorthand("AvN").unwrap(),
+            [WordType::Adjective, WordType::Verb, WordType::Noun]
+        );
+        let letters: std::collections::HashSet<WordType> = "APRXCDINTOMUSYV"
+            .chars()
+            .filter_map(WordType::from_shorthand)
+            .collect();
+        assert_eq!(letters.len(), WordType::ALL.len());
+        assert!(WordType::parse_shorthand("AB").is_err());
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        for word_type in WordType::ALL {
+            assert_eq!(
+                word_type.to_string().parse::<WordType>().unwrap(),
+                word_type
+            );
+            let upper = word_type.to_string().to_uppercase();
+            assert_eq!(upper.parse::<WordType>().unwrap(), word_type);
+        }
+        assert!(matches!(
+            "nouns".parse::<WordType>(),
+            Err(FluentCodesError::UnknownTable(token)) if token == "nouns"
+        ));
+    }
+}